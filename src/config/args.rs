@@ -1,364 +1,536 @@
 use crate::{
     config::{
-        structs::Config,
-        utils::{convert_to_string_if_some, parse_request},
+        structs::{Config, Target, DEFAULT_CUSTOM_PARAMETERS, DEFAULT_CUSTOM_VALUES},
+        utils::{
+            add_cookies_injection_point, convert_to_string_if_some, parse_request, read_request_files,
+            request_filenames, set_verbatim, without_injection_lines, RequestFilter,
+        },
     },
-    network::utils::{DataType, Headers},
+    network::{
+        rate::{parse_rate, RateLimiter},
+        retry::{ErrorClass, RetryPolicy},
+        utils::{DataType, Headers},
+    },
+    utils::read_stdin_lines,
 };
-use clap::{crate_version, App, AppSettings, Arg};
-use std::{collections::HashMap, error::Error, fs, io::{self, Write}};
+use clap::{crate_version, App, AppSettings, Arg, ArgGroup, ArgMatches, SubCommand};
+use regex::Regex;
+use std::{collections::HashMap, error::Error, fs, io::{self, Write}, sync::Arc};
 use tokio::time::Duration;
 use url::Url;
 
-use super::utils::{read_urls_if_possible, mimic_browser_headers, add_default_headers};
+use super::{
+    burp::read_burp_xml,
+    curl::parse_curl,
+    file::{read_config_file, Args},
+    mutations::Mutations,
+    wordlist::{write_builtin_wordlists, WordlistFile},
+    har::read_har,
+    openapi::read_openapi,
+    postman::read_postman,
+    utils::{read_urls_if_possible, mimic_browser_headers, add_default_headers},
+};
 
-pub fn get_config() -> Result<Config, Box<dyn Error>> {
-    let app = App::new("x8")
-        .setting(AppSettings::ArgRequiredElseHelp)
-        .version(crate_version!())
-        .author("sh1yo <sh1yo@tuta.io>")
-        .about("Hidden parameters discovery suite")
-        .arg(Arg::with_name("url")
+/// arguments of the main command. Their names are the keys of the config file as well
+fn main_args() -> Vec<(&'static str, Arg<'static, 'static>)> {
+    vec![
+        ("url", Arg::with_name("url")
             .short("u")
             .long("url")
             .help("You can add a custom injection point with %s.\nMultiple urls and filenames are supported:\n-u filename.txt\n-u https://url1 http://url2")
             .takes_value(true)
             .min_values(1)
-            .conflicts_with("request")
-        )
-        .arg(Arg::with_name("request")
+            .conflicts_with("request")),
+        ("request", Arg::with_name("request")
             .short("r")
             .long("request")
-            .help("The file with the raw http request")
+            .help("The file with the raw http request.\nA directory or a glob pattern (like 'requests/*.req') can be provided to check multiple request files")
             .takes_value(true)
-            .conflicts_with("url")
-        )
-        .arg(Arg::with_name("proto")
+            .conflicts_with("url")),
+        ("proto", Arg::with_name("proto")
             .long("proto")
             .help("Protocol to use with request file (default is \"https\")")
             .takes_value(true)
             .requires("request")
-            .conflicts_with("url")
-        )
-        .arg(Arg::with_name("port")
+            .conflicts_with("url")),
+        ("port", Arg::with_name("port")
             .long("port")
             .short("-p")
             .help("Port to use with request file")
             .takes_value(true)
             .requires("request")
-            .conflicts_with("url")
-        )
-        .arg(Arg::with_name("split-by")
+            .conflicts_with("url")),
+        ("split-by", Arg::with_name("split-by")
             .long("split-by")
             .help("Split the request into lines by the provided sequence. By default splits by \\r, \\n and \\r\\n")
             .takes_value(true)
             .requires("request")
-            .conflicts_with("url")
-        )
-        .arg(
-            Arg::with_name("wordlist")
-                .short("w")
-                .long("wordlist")
-                .help("The file with parameters (leave empty to read from stdin)")
-                .default_value("")
-                .takes_value(true),
-        )
-        .arg(
-            Arg::with_name("parameter-template")
-                .short("P")
-                .long("param-template")
-                .help("%k - key, %v - value. Example: --param-template 'user[%k]=%v'\nDefault: urlencoded - <%k=%v>, json - <\"%k\":%v>, headers - <%k=%v>")
-                .takes_value(true),
-        )
-        .arg(
-            Arg::with_name("joiner")
+            .conflicts_with("url")),
+        ("verbatim", Arg::with_name("verbatim")
+            .long("verbatim")
+            .help("Send the request file exactly as it is: header order, case, duplicates and line endings are preserved.\nOnly %s and the Content-Length header's value are changed.\nOnly HTTP/1 is supported: the request is written to the connection as is")
+            .requires("request")
+            .conflicts_with_all(&["replay-proxy", "http"])),
+        ("curl-file", Arg::with_name("curl-file")
+            .long("curl-file")
+            .help("The file with the curl command (like the one from \"Copy as cURL\"). Use '-' to read the command from stdin")
+            .takes_value(true)
+            .conflicts_with_all(&["url", "request", "method", "body", "headers"])),
+        ("har", Arg::with_name("har")
+            .long("har")
+            .help("The HAR file with requests. Every entry is checked with its own method, url, headers and body")
+            .takes_value(true)
+            .conflicts_with_all(&["url", "request", "curl-file", "method", "body", "headers"])),
+        ("burp-xml", Arg::with_name("burp-xml")
+            .long("burp-xml")
+            .help("The file with requests exported via Burp Suite's \"Save items\". <protocol>, <host> and <port> elements are used to create urls")
+            .takes_value(true)
+            .conflicts_with_all(&["url", "request", "curl-file", "har", "method", "body", "headers", "proto", "port"])),
+        ("postman", Arg::with_name("postman")
+            .long("postman")
+            .help("The Postman v2.1 collection. Every request is checked with its own method, url, headers, auth and body")
+            .takes_value(true)
+            .conflicts_with_all(&["url", "request", "curl-file", "har", "burp-xml", "method", "body", "headers"])),
+        ("postman-env", Arg::with_name("postman-env")
+            .long("postman-env")
+            .help("The Postman environment file to resolve {{variables}} from. Has a priority against the collection variables")
+            .takes_value(true)
+            .requires("postman")),
+        ("openapi", Arg::with_name("openapi")
+            .long("openapi")
+            .help("The OpenAPI (or Swagger) json specification. Every operation is checked with the documented parameters and their example values")
+            .takes_value(true)
+            .conflicts_with_all(&["url", "request", "curl-file", "har", "burp-xml", "postman", "method", "body"])),
+        ("base-url", Arg::with_name("base-url")
+            .long("base-url")
+            .help("The url to prepend to the paths from the OpenAPI specification. By default the first server url is used")
+            .takes_value(true)
+            .requires("openapi")),
+        ("include-host", Arg::with_name("include-host")
+            .long("include-host")
+            .help("Check only imported requests to these hosts and their subdomains")
+            .takes_value(true)
+            .min_values(1)),
+        ("exclude-host", Arg::with_name("exclude-host")
+            .long("exclude-host")
+            .help("Skip imported requests to these hosts and their subdomains")
+            .takes_value(true)
+            .min_values(1)),
+        ("include-path", Arg::with_name("include-path")
+            .long("include-path")
+            .help("Check only imported requests with the path (and query) matching the regex")
+            .takes_value(true)),
+        ("exclude-path", Arg::with_name("exclude-path")
+            .long("exclude-path")
+            .help("Skip imported requests with the path (and query) matching the regex")
+            .takes_value(true)),
+        ("include-mime", Arg::with_name("include-mime")
+            .long("include-mime")
+            .help("Check only imported requests with the response's MIME type containing one of the values.\nExample: --include-mime html json")
+            .takes_value(true)
+            .min_values(1)),
+        ("exclude-mime", Arg::with_name("exclude-mime")
+            .long("exclude-mime")
+            .help("Skip imported requests with the response's MIME type containing one of the values.\nExample: --exclude-mime image javascript css")
+            .takes_value(true)
+            .min_values(1)),
+        ("wordlist", Arg::with_name("wordlist")
+            .short("w")
+            .long("wordlist")
+            .help("The file with parameters (leave empty to read from stdin or to use the built-in wordlists).\nbuiltin:<name> selects one of the built-in wordlists (--list-wordlists).\nCan be used multiple times. Wordlists are merged without duplicates.\nPrefix the file with query:, body:, json:, headers: or header-value: to use it only for that injection place.\nExample: -w params.txt -w headers:headers.txt")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)),
+        ("list-wordlists", Arg::with_name("list-wordlists")
+            .long("list-wordlists")
+            .help("Print the built-in wordlists and exit")),
+        ("mine-scripts", Arg::with_name("mine-scripts")
+            .long("mine-scripts")
+            .help("Fetch same-origin scripts from the initial response (and their source maps)\nand check the parameter names found within them")),
+        ("mutate", Arg::with_name("mutate")
+            .long("mutate")
+            .help("Generate variants of the wordlist entries.\nAvailable: camel, snake, kebab, plural, affixes, all\nExample: --mutate snake camel plural")
+            .takes_value(true)
+            .min_values(1)
+            .possible_values(&["camel", "snake", "kebab", "plural", "affixes", "all"])),
+        ("prefixes", Arg::with_name("prefixes")
+            .long("prefixes")
+            .help("Prefixes to add to the wordlist entries. Default for --mutate affixes is \"_ is\"")
+            .takes_value(true)
+            .min_values(1)),
+        ("suffixes", Arg::with_name("suffixes")
+            .long("suffixes")
+            .help("Suffixes to add to the wordlist entries. Default for --mutate affixes is \"Id []\"")
+            .takes_value(true)
+            .min_values(1)),
+        ("parameter-template", Arg::with_name("parameter-template")
+            .short("P")
+            .long("param-template")
+            .help("%k - key, %v - value. Example: --param-template 'user[%k]=%v'\nDefault: urlencoded - <%k=%v>, json - <\"%k\":%v>, headers - <%k=%v>")
+            .takes_value(true)),
+        ("joiner", Arg::with_name("joiner")
             .short("j")
             .long("joiner")
             .help("How to join parameter templates. Example: --joiner '&'\nDefault: urlencoded - '&', json - ', ', header values - '; '")
-            .takes_value(true),
-        )
-        .arg(
-            Arg::with_name("body")
-                .short("b")
-                .long("body")
-                .help("Example: --body '{\"x\":{%s}}'\nAvailable variables: {{random}}")
-                .value_name("body")
-                .conflicts_with("request")
-        )
-        .arg(
-            Arg::with_name("data-type")
-                .short("t")
-                .long("data-type")
-                .help("Available: urlencode, json\nCan be detected automatically if --body is specified (default is \"urlencode\")")
-                .value_name("data-type")
-        )
-        .arg(
-            Arg::with_name("proxy")
-                .short("x")
-                .long("proxy")
-                .value_name("proxy")
-                .takes_value(true)
-        )
-        .arg(
-            Arg::with_name("burp-proxy")
-                .short("B")
-                .help("Equal to -x http://localhost:8080")
-                .conflicts_with("proxy")
-        )
-        .arg(
-            Arg::with_name("delay")
-                .short("d")
-                .long("delay")
-                .value_name("Delay between requests in milliseconds")
-                .default_value("0")
-                .takes_value(true)
-        )
-        .arg(
-            Arg::with_name("output")
-                .short("o")
-                .long("output")
-                .value_name("file")
-                .takes_value(true)
-        )
-        .arg(
-            Arg::with_name("output-format")
-                .short("O")
-                .long("output-format")
-                .help("standart, json, url, request")
-                .default_value("standart")
-                .takes_value(true)
-        )
-        .arg(
-            Arg::with_name("append")
-                .long("append")
-                .help("Append to the output file instead of overwriting it.")
-        )
-        .arg(
-            Arg::with_name("remove-empty")
-                .long("remove-empty")
-                .requires("output")
-                .help("Skip writing to file outputs of url:method pairs without found parameters")
-        )
-        .arg(
-            Arg::with_name("method")
-                .short("X")
-                .long("method")
-                .value_name("methods")
-                .help("Multiple values are supported: -X GET POST")
-                .takes_value(true)
-                .min_values(1)
-                .conflicts_with("request")
-        )
-        .arg(
-            Arg::with_name("headers")
-                .short("H")
-                .help("Example: -H 'one:one' 'two:two'")
-                .takes_value(true)
-                .min_values(1)
-                .conflicts_with("request")
-        )
-        .arg(
-            Arg::with_name("invert")
-                .long("invert")
-                .help("By default, parameters are sent within the body only in case POST,PUT,PATCH,DELETE methods are used.
+            .takes_value(true)),
+        ("body", Arg::with_name("body")
+            .short("b")
+            .long("body")
+            .help("Example: --body '{\"x\":{%s}}'\nAvailable variables: {{random}}")
+            .value_name("body")
+            .conflicts_with("request")),
+        ("data-type", Arg::with_name("data-type")
+            .short("t")
+            .long("data-type")
+            .help("Available: urlencode, json\nCan be detected automatically if --body is specified (default is \"urlencode\")")
+            .value_name("data-type")),
+        ("proxy", Arg::with_name("proxy")
+            .short("x")
+            .long("proxy")
+            .value_name("proxy")
+            .takes_value(true)),
+        ("burp-proxy", Arg::with_name("burp-proxy")
+            .short("B")
+            .help("Equal to -x http://localhost:8080")
+            .conflicts_with("proxy")),
+        ("delay", Arg::with_name("delay")
+            .short("d")
+            .long("delay")
+            .value_name("Delay between requests in milliseconds")
+            .default_value("0")
+            .takes_value(true)),
+        ("rate", Arg::with_name("rate")
+            .long("rate")
+            .help("The max amount of requests across all the workers. Example: --rate 10/s\nAvailable units: s, m, h")
+            .takes_value(true)),
+        ("retries", Arg::with_name("retries")
+            .long("retries")
+            .help("How many times to retry failed requests. Delays between retries grow exponentially")
+            .default_value("1")
+            .takes_value(true)),
+        ("retry-on", Arg::with_name("retry-on")
+            .long("retry-on")
+            .help("Retry only requests that failed with these errors (default is all of them)")
+            .takes_value(true)
+            .min_values(1)
+            .possible_values(&["timeout", "reset", "tls", "dns", "other"])),
+        ("rate-limit-signatures", Arg::with_name("rate-limit-signatures")
+            .long("rate-limit-signatures")
+            .help("Regexes that mean the server limits the rate of requests when found within the response.\n429 and 503 with Retry-After are detected by default.\nExample: --rate-limit-signatures '(?i)too many requests' 'captcha'")
+            .takes_value(true)
+            .min_values(1)),
+        ("host-rate", Arg::with_name("host-rate")
+            .long("host-rate")
+            .help("The max amount of requests to a single host. Example: --host-rate 3/s")
+            .takes_value(true)),
+        ("output", Arg::with_name("output")
+            .short("o")
+            .long("output")
+            .value_name("file")
+            .takes_value(true)),
+        ("output-format", Arg::with_name("output-format")
+            .short("O")
+            .long("output-format")
+            .help("standart, json, jsonl, sarif, html, csv, markdown, url, request, curl")
+            .default_value("standart")
+            .takes_value(true)),
+        ("append", Arg::with_name("append")
+            .long("append")
+            .help("Append to the output file instead of overwriting it.")),
+        ("baseline", Arg::with_name("baseline")
+            .long("baseline")
+            .value_name("file")
+            .help("The json output of the previous scan. Found parameters are marked as new, present or gone.\nThe exit code is 2 in case new parameters are found")
+            .takes_value(true)),
+        ("only-new", Arg::with_name("only-new")
+            .long("only-new")
+            .requires("baseline")
+            .help("Report only parameters that weren't found within the baseline")),
+        ("db", Arg::with_name("db")
+            .long("db")
+            .value_name("file")
+            .help("Record the scan to the sqlite database. The results can be queried later via x8 db query")
+            .takes_value(true)),
+        ("remove-empty", Arg::with_name("remove-empty")
+            .long("remove-empty")
+            .requires("output")
+            .help("Skip writing to file outputs of url:method pairs without found parameters")),
+        ("method", Arg::with_name("method")
+            .short("X")
+            .long("method")
+            .value_name("methods")
+            .help("Multiple values are supported: -X GET POST")
+            .takes_value(true)
+            .min_values(1)
+            .conflicts_with("request")),
+        ("headers", Arg::with_name("headers")
+            .short("H")
+            .help("Example: -H 'one:one' 'two:two'")
+            .takes_value(true)
+            .min_values(1)
+            .conflicts_with("request")),
+        ("invert", Arg::with_name("invert")
+            .long("invert")
+            .help("By default, parameters are sent within the body only in case POST,PUT,PATCH,DELETE methods are used.
 It's possible to overwrite this behavior by specifying the option")
-                .conflicts_with("headers-discovery")
-        )
-        .arg(
-            Arg::with_name("headers-discovery")
-                .long("headers")
-                .help("Switch to header discovery mode.\nNOTE Content-Length and Host headers are automatically removed from the list")
-                .conflicts_with("invert")
-                .conflicts_with("param-template")
-        )
-        .arg(
-            Arg::with_name("force")
-                .long("force")
-                .help("Force searching for parameters on pages > 25MB. Remove an error in case there's 1 worker with --one-worker-per-host option.")
-        )
-        .arg(
-            Arg::with_name("disable-custom-parameters")
-                .long("disable-custom-parameters")
-                .help("Do not automatically check parameters like admin=true")
-        )
-        .arg(
-            Arg::with_name("disable-colors")
-                .long("disable-colors")
-        )
-        .arg(
-            Arg::with_name("force-enable-colors")
-                .long("force-enable-colors")
-        )
-        .arg(
-            Arg::with_name("disable-trustdns")
-                .long("disable-trustdns")
-                .help("Can solve some dns related problems")
-        )
-        .arg(
-            Arg::with_name("disable-progress-bar")
-                .long("disable-progress-bar")
-        )
-        .arg(
-            Arg::with_name("progress-bar-len")
-                .long("progress-bar-len")
-                .default_value("26")
-        )
-        .arg(
-            Arg::with_name("replay-once")
-                .long("replay-once")
-                .help("If a replay proxy is specified, send all found parameters within one request.")
-                .requires("replay-proxy")
-        )
-        .arg(
-            Arg::with_name("replay-proxy")
-                .takes_value(true)
-                .long("replay-proxy")
-                .help("Request target with every found parameter via the replay proxy at the end.")
-        )
-        .arg(
-            Arg::with_name("custom-parameters")
-                .long("custom-parameters")
-                .help("Check these parameters with non-random values like true/false yes/no\n(default is \"admin bot captcha debug disable encryption env show sso test waf\")")
-                .takes_value(true)
-                .min_values(1)
-                .conflicts_with("disable-custom-parameters")
-        )
-        .arg(
-            Arg::with_name("custom-values")
-                .long("custom-values")
-                .help("Values for custom parameters (default is \"1 0 false off null true yes no\")")
-                .takes_value(true)
-                .min_values(1)
-                .conflicts_with("disable-custom-parameters")
-        )
-        .arg(
-            Arg::with_name("follow-redirects")
-                .long("follow-redirects")
-                .short("L")
-                .help("Follow redirections")
-        )
-        .arg(
-            Arg::with_name("encode")
-                .long("encode")
-                .help("Encodes query or body before making a request, i.e & -> %26, = -> %3D\nList of chars to encode: \", `, , <, >, &, #, ;, /, =, %")
-        )
-        .arg(
-            Arg::with_name("strict")
-                .long("strict")
-                .help("Only report parameters that have changed the different parts of a page")
-        )
-        .arg(
-            Arg::with_name("test")
-                .long("test")
-                .help("Prints request and response")
-        )
-        .arg(
-            Arg::with_name("verbose")
-                .long("verbose")
-                .short("v")
-                .help("Verbose level 0/1/2")
-                .default_value("1")
-                .takes_value(true)
-        )
-        .arg(
-            Arg::with_name("save-responses")
-                .long("save-responses")
-                .help("Save request and response to a directory when a parameter is found")
-                .takes_value(true)
-        )
-        .arg(
-            Arg::with_name("learn-requests-count")
-                .long("learn-requests")
-                .help("Set the custom number of learn requests.")
-                .default_value("9")
-                .takes_value(true)
-        )
-        .arg(
-            Arg::with_name("recursion-depth")
-                .long("recursion-depth")
-                .help("Check the same list of parameters with the found parameters until there are no new parameters to be found.
+            .conflicts_with("headers-discovery")),
+        ("headers-discovery", Arg::with_name("headers-discovery")
+            .long("headers")
+            .help("Switch to header discovery mode.\nNOTE Content-Length and Host headers are automatically removed from the list")
+            .conflicts_with("invert")
+            .conflicts_with("param-template")),
+        ("force", Arg::with_name("force")
+            .long("force")
+            .help("Force searching for parameters on pages > 25MB. Remove an error in case there's 1 worker with --one-worker-per-host option.")),
+        ("disable-custom-parameters", Arg::with_name("disable-custom-parameters")
+            .long("disable-custom-parameters")
+            .help("Do not automatically check parameters like admin=true")),
+        ("disable-colors", Arg::with_name("disable-colors")
+            .long("disable-colors")),
+        ("force-enable-colors", Arg::with_name("force-enable-colors")
+            .long("force-enable-colors")),
+        ("disable-trustdns", Arg::with_name("disable-trustdns")
+            .long("disable-trustdns")
+            .help("Can solve some dns related problems")),
+        ("disable-progress-bar", Arg::with_name("disable-progress-bar")
+            .long("disable-progress-bar")),
+        ("progress-bar-len", Arg::with_name("progress-bar-len")
+            .long("progress-bar-len")
+            .default_value("26")),
+        ("replay-once", Arg::with_name("replay-once")
+            .long("replay-once")
+            .help("If a replay proxy is specified, send all found parameters within one request.")
+            .requires("replay-proxy")),
+        ("replay-proxy", Arg::with_name("replay-proxy")
+            .takes_value(true)
+            .long("replay-proxy")
+            .help("Request target with every found parameter via the replay proxy at the end.")),
+        ("custom-parameters", Arg::with_name("custom-parameters")
+            .long("custom-parameters")
+            .help("Check these parameters with non-random values like true/false yes/no\n(default is \"admin bot captcha debug disable encryption env show sso test waf\")")
+            .takes_value(true)
+            .min_values(1)
+            .conflicts_with("disable-custom-parameters")),
+        ("custom-values", Arg::with_name("custom-values")
+            .long("custom-values")
+            .help("Values for custom parameters (default is \"1 0 false off null true yes no\")")
+            .takes_value(true)
+            .min_values(1)
+            .conflicts_with("disable-custom-parameters")),
+        ("follow-redirects", Arg::with_name("follow-redirects")
+            .long("follow-redirects")
+            .short("L")
+            .help("Follow redirections")),
+        ("encode", Arg::with_name("encode")
+            .long("encode")
+            .help("Encodes query or body before making a request, i.e & -> %26, = -> %3D\nList of chars to encode: \", `, , <, >, &, #, ;, /, =, %")),
+        ("strict", Arg::with_name("strict")
+            .long("strict")
+            .help("Only report parameters that have changed the different parts of a page")),
+        ("test", Arg::with_name("test")
+            .long("test")
+            .help("Prints request and response")),
+        ("verbose", Arg::with_name("verbose")
+            .long("verbose")
+            .short("v")
+            .help("Verbose level 0/1/2")
+            .default_value("1")
+            .takes_value(true)),
+        ("save-responses", Arg::with_name("save-responses")
+            .long("save-responses")
+            .help("Save request and response to a directory when a parameter is found")
+            .takes_value(true)),
+        ("learn-requests-count", Arg::with_name("learn-requests-count")
+            .long("learn-requests")
+            .help("Set the custom number of learn requests.")
+            .default_value("9")
+            .takes_value(true)),
+        ("recursion-depth", Arg::with_name("recursion-depth")
+            .long("recursion-depth")
+            .help("Check the same list of parameters with the found parameters until there are no new parameters to be found.
 Conflicts with --verify for now.")
-                .takes_value(true)
-                .conflicts_with("verify")
-        )
-        .arg(
-            Arg::with_name("max")
-                .short("m")
-                .long("max")
-                .help("Change the maximum number of parameters per request.\n(default is <= 256 for query, 64 for headers and 512 for body)")
-                .takes_value(true)
-        )
-        .arg(
-            Arg::with_name("timeout")
-                .long("timeout")
-                .help("HTTP request timeout in seconds.")
-                .default_value("15")
-                .takes_value(true)
-        )
-        .arg(
-            Arg::with_name("concurrency")
-                .short("c")
-                .help("The number of concurrent requests per url")
-                .default_value("1")
-                .takes_value(true)
-        )
-        .arg(
-            Arg::with_name("workers")
-                .short("W")
-                .long("workers")
-                .help("The number of concurrent url checks.\nUse -W0 to run everything in parallel")
-                .default_value("1")
-                .takes_value(true)
-        )
-        .arg(
-            Arg::with_name("verify")
-                .long("verify")
-                .help("Verify found parameters.")
-        )
-        .arg(
-            Arg::with_name("reflected-only")
-                .long("reflected-only")
-                .help("Disable page comparison and search for reflected parameters only.")
-        )
-        .arg(
-            Arg::with_name("one-worker-per-host")
-                .long("one-worker-per-host")
-                .help("Multiple urls with the same host will be checked one after another,\nwhile urls with different hosts - are in parallel.\nDoesn't increase the number of workers")
+            .takes_value(true)
+            .conflicts_with("verify")),
+        ("max", Arg::with_name("max")
+            .short("m")
+            .long("max")
+            .help("Change the maximum number of parameters per request.\n(default is <= 256 for query, 64 for headers and 512 for body)")
+            .takes_value(true)),
+        ("timeout", Arg::with_name("timeout")
+            .long("timeout")
+            .help("HTTP request timeout in seconds.")
+            .default_value("15")
+            .takes_value(true)),
+        ("concurrency", Arg::with_name("concurrency")
+            .short("c")
+            .help("The number of concurrent requests per url")
+            .default_value("1")
+            .takes_value(true)),
+        ("workers", Arg::with_name("workers")
+            .short("W")
+            .long("workers")
+            .help("The number of concurrent url checks.\nUse -W0 to run everything in parallel")
+            .default_value("1")
+            .takes_value(true)),
+        ("verify", Arg::with_name("verify")
+            .long("verify")
+            .help("Verify found parameters.")),
+        ("reflected-only", Arg::with_name("reflected-only")
+            .long("reflected-only")
+            .help("Disable page comparison and search for reflected parameters only.")),
+        ("one-worker-per-host", Arg::with_name("one-worker-per-host")
+            .long("one-worker-per-host")
+            .help("Multiple urls with the same host will be checked one after another,\nwhile urls with different hosts - are in parallel.\nDoesn't increase the number of workers")),
+        ("mimic-browser", Arg::with_name("mimic-browser")
+            .long("mimic-browser")
+            .help("Add default headers that browsers usually set.")
+            .conflicts_with("request")),
+        ("http", Arg::with_name("http")
+            .long("http")
+            .help("HTTP version. Supported versions: --http 1.1, --http 2")
+            .takes_value(true)),
+        ("check-binary", Arg::with_name("check-binary")
+            .long("check-binary")
+            .help("Check the body of responses with binary content types")),
+        ("cookies", Arg::with_name("cookies")
+            .long("cookies")
+            .help("Shortcut for adding injection point to cookies")),
+        ("remove-banner", Arg::with_name("remove-banner")
+            .long("remove-banner")
+            .help("Do not print initial banner")),
+        ("state", Arg::with_name("state")
+            .long("state")
+            .help("Save the progress of the scan to the file. The scan can be continued later with --resume")
+            .takes_value(true)
+            .conflicts_with("resume")),
+        ("resume", Arg::with_name("resume")
+            .long("resume")
+            .help("Continue the scan from the state file. Finished url:method pairs are skipped and their results are added to the output")
+            .takes_value(true)
+            .conflicts_with("state")),
+        ("config", Arg::with_name("config")
+            .long("config")
+            .help("The config file with default values for arguments.\nBy default ./x8.toml and ~/.config/x8/config.toml are checked")
+            .takes_value(true)),
+        ("profile", Arg::with_name("profile")
+            .long("profile")
+            .help("Apply values from the [profile.<name>] table of the config file")
+            .takes_value(true)),
+    ]
+}
+
+pub(super) fn app() -> App<'static, 'static> {
+    App::new("x8")
+        .setting(AppSettings::ArgRequiredElseHelp)
+        .version(crate_version!())
+        .author("sh1yo <sh1yo@tuta.io>")
+        .about("Hidden parameters discovery suite")
+        .args(&main_args().into_iter().map(|(_, arg)| arg).collect::<Vec<_>>())
+        .subcommand(
+            SubCommand::with_name("db")
+                .about("Work with the database of results (--db)")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("query")
+                        .about("List parameters found on the host or endpoints where the parameter was found")
+                        .arg(
+                            Arg::with_name("db")
+                                .long("db")
+                                .value_name("file")
+                                .required(true)
+                                .takes_value(true)
+                        )
+                        .arg(
+                            Arg::with_name("host")
+                                .long("host")
+                                .help("Subdomains are included as well")
+                                .takes_value(true)
+                        )
+                        .arg(
+                            Arg::with_name("param")
+                                .long("param")
+                                .takes_value(true)
+                        )
+                        .group(ArgGroup::with_name("filter").args(&["host", "param"]).required(true))
+                )
         )
-        .arg(
-            Arg::with_name("mimic-browser")
-                .long("mimic-browser")
-                .help("Add default headers that browsers usually set.")
-                .conflicts_with("request")
+}
+
+/// the only keys the config file can contain
+pub(super) fn arg_names() -> Vec<&'static str> {
+    main_args().into_iter().map(|(name, _)| name).collect()
+}
+
+/// the main command where every argument is named --<name>.
+/// Config file keys and values are checked by clap the same way as the command line arguments
+pub(super) fn config_app() -> App<'static, 'static> {
+    App::new("x8")
+        .setting(AppSettings::ColorNever)
+        .setting(AppSettings::DisableHelpFlags)
+        .setting(AppSettings::DisableVersion)
+        .args(
+            &main_args()
+                .into_iter()
+                .map(|(name, arg)| arg.long(name).allow_hyphen_values(true))
+                .collect::<Vec<_>>(),
         )
-        .arg(
-            Arg::with_name("http")
-                .long("http")
-                .help("HTTP version. Supported versions: --http 1.1, --http 2")
-                .takes_value(true)
-        ).arg(
-            Arg::with_name("check-binary")
-                .long("check-binary")
-                .help("Check the body of responses with binary content types")
-        ).arg(
-            Arg::with_name("cookies")
-                .long("cookies")
-                .help("Shortcut for adding injection point to cookies")
-        ).arg(
-            Arg::with_name("remove-banner")
-                .long("remove-banner")
-                .help("Do not print initial banner")
-        );
-
-    let args = app.clone().get_matches();
-
-    if args.value_of("url").is_none() && args.value_of("request").is_none() {
+}
+
+/// parses the command line arguments. Subcommands are dispatched by the caller
+pub fn get_matches() -> ArgMatches<'static> {
+    app().get_matches()
+}
+
+pub fn get_config(matches: ArgMatches<'static>) -> Result<Config, Box<dyn Error>> {
+    let app = config_app();
+
+    // command line arguments have a priority against config file values
+    let config_file = read_config_file(matches.value_of("config"), matches.value_of("profile"), &app)?;
+    let args = Args::new(matches, config_file, &app, &arg_names());
+
+    if args.is_present("list-wordlists") {
+        write_builtin_wordlists();
+        std::process::exit(0);
+    }
+
+    if args.value_of("url").is_none()
+        && args.value_of("request").is_none()
+        && args.value_of("curl-file").is_none()
+        && args.value_of("har").is_none()
+        && args.value_of("burp-xml").is_none()
+        && args.value_of("postman").is_none()
+        && args.value_of("openapi").is_none()
+    {
         Err("A target was not provided")?;
     }
 
     // parse numbers
     let delay = Duration::from_millis(args.value_of("delay").unwrap().parse()?);
 
+    let rate_limiter = RateLimiter::new(
+        args.value_of("rate").map(parse_rate).transpose()?,
+        args.value_of("host-rate").map(parse_rate).transpose()?,
+    );
+
+    let mut retry_policy = RetryPolicy {
+        retries: args.value_of("retries").unwrap().parse()?,
+        ..Default::default()
+    };
+    if let Some(classes) = args.values_of("retry-on") {
+        retry_policy.classes = classes
+            .iter()
+            .map(|x| x.parse())
+            .collect::<Result<Vec<ErrorClass>, String>>()?;
+    }
+
+    let mut rate_limit_signatures = Vec::new();
+    for signature in args.values_of("rate-limit-signatures").unwrap_or_default() {
+        rate_limit_signatures.push(match Regex::new(signature) {
+            Ok(val) => val,
+            Err(err) => Err(format!("Unable to parse the rate limit signature {}: {}", signature, err))?,
+        });
+    }
+
     let learn_requests_count = args.value_of("learn-requests-count").unwrap().parse()?;
     let concurrency = args.value_of("concurrency").unwrap().parse()?;
     let workers = args.value_of("workers").unwrap().parse()?;
@@ -379,8 +551,40 @@ So there's no point in --one-worker-per-host with 1 worker. \
 Increase the amount of workers to remove the error or use --force.")?;
     }
 
+    // -r accepts a directory or a glob pattern as well
+    let request_filenames = match args.value_of("request") {
+        Some(val) => request_filenames(val)?,
+        None => Vec::new(),
+    };
+
     // try to read request file
-    let request = match args.value_of("request") {
+    // multiple request files are checked as separate targets
+    let request = if request_filenames.len() == 1 {
+        fs::read_to_string(&request_filenames[0])?
+    } else {
+        String::new()
+    };
+
+    // get protocol (https/http) and port for request files
+    let scheme = args
+        .value_of("proto")
+        .unwrap_or("https")
+        .replace("://", "");
+
+    let port: Option<u16> = if args.value_of("port").is_some() {
+        Some(args.value_of("port").unwrap().parse()?)
+    } else {
+        None
+    };
+
+    let curl = match args.value_of("curl-file") {
+        Some("-") => {
+            // otherwise the wordlist would be read from stdin as well
+            if args.values_of("wordlist").unwrap_or_default().is_empty() {
+                Err("Provide the wordlist via -w when the curl command is read from stdin")?;
+            }
+            read_stdin_lines().join("\n")
+        }
         Some(val) => fs::read_to_string(val)?,
         None => String::new(),
     };
@@ -398,104 +602,163 @@ Increase the amount of workers to remove the error or use --force.")?;
         None => None
     };
 
+    let http_version = if args.value_of("http").is_some() {
+        match  args.value_of("http").unwrap() {
+            "1.1" => Some(http::Version::HTTP_11),
+            "2" => Some(http::Version::HTTP_2),
+            _ => {
+                writeln!(
+                    io::stdout(),
+                    "[#] Incorrect http version provided. The argument is ignored"
+                ).ok();
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let filter = RequestFilter {
+        include_hosts: args.values_of("include-host").unwrap_or_default().iter().map(|x| x.to_string()).collect(),
+        exclude_hosts: args.values_of("exclude-host").unwrap_or_default().iter().map(|x| x.to_string()).collect(),
+        include_path: args.value_of("include-path").map(Regex::new).transpose()?,
+        exclude_path: args.value_of("exclude-path").map(Regex::new).transpose()?,
+        include_mime: args.values_of("include-mime").unwrap_or_default().iter().map(|x| x.to_lowercase()).collect(),
+        exclude_mime: args.values_of("exclude-mime").unwrap_or_default().iter().map(|x| x.to_lowercase()).collect(),
+    };
+
+    let mut headers: HashMap<&str, String> = HashMap::new();
+
+    if let Some(val) = args.values_of("headers") {
+        for header in val {
+            let mut k_v = header.split(':');
+            let key = match k_v.next() {
+                Some(val) => val,
+                None => Err("Unable to parse headers")?,
+            };
+            let value = [
+                match k_v.next() {
+                    Some(val) => val.trim().to_owned(),
+                    None => Err("Unable to parse headers")?,
+                },
+                k_v.map(|x| ":".to_owned() + x).collect(),
+            ]
+            .concat();
+
+            headers.insert(key, value);
+        }
+    };
+
+    // set default headers if weren't specified by a user.
+    let user_headers = if args.is_present("mimic-browser") {
+        mimic_browser_headers(headers)
+    } else {
+        add_default_headers(headers)
+    };
+
+    // requests imported from other formats have their own method, url, headers and body
+    let imported_targets = if request_filenames.len() > 1
+        || args.value_of("har").is_some()
+        || args.value_of("burp-xml").is_some()
+        || args.value_of("postman").is_some()
+        || args.value_of("openapi").is_some()
+    {
+        let mut targets = if request_filenames.len() > 1 {
+            read_request_files(
+                &request_filenames,
+                &scheme,
+                port,
+                data_type.clone(),
+                args.value_of("split-by"),
+                &filter,
+                args.is_present("verbatim"),
+                args.is_present("headers-discovery"),
+            )?
+        } else if let Some(filename) = args.value_of("har") {
+            read_har(filename, &filter)?
+        } else if let Some(filename) = args.value_of("burp-xml") {
+            read_burp_xml(filename, &filter)?
+        } else if let Some(filename) = args.value_of("postman") {
+            read_postman(filename, args.value_of("postman-env"), &filter)?
+        } else {
+            // the spec doesn't contain any headers except the documented ones, so -H and the default headers are used
+            read_openapi(args.value_of("openapi").unwrap(), args.value_of("base-url"), &user_headers, &filter)?
+        };
+
+        // --data-type has a priority against the detected one
+        if data_type.is_some() {
+            targets.iter_mut().for_each(|x| x.data_type = data_type.clone());
+        }
+
+        Some(targets)
+    } else {
+        None
+    };
+
     // parse the default request information
     // either via the request file or via provided parameters
-    let (methods, urls, mut headers, body, data_type, http_version) = if !request.is_empty() {
-        // if the request file is specified - get protocol (https/http) from args, specify scheme and port, and parse request file
-        let proto = args
-            .value_of("proto")
-            .unwrap_or("https")
-            .to_string();
+    let (methods, urls, mut headers, body, data_type, http_version) = if let Some(targets) = &imported_targets {
+        let mut methods: Vec<String> = Vec::new();
+        let mut urls: Vec<String> = Vec::new();
 
-        let scheme = proto.replace("://", "");
+        for target in targets {
+            if !methods.contains(&target.method) {
+                methods.push(target.method.clone());
+            }
+            if !urls.contains(&target.url) {
+                urls.push(target.url.clone());
+            }
+        }
 
-        let port: Option<u16> = if args.value_of("port").is_some() {
-            Some(args.value_of("port").unwrap().parse()?)
+        (methods, urls, Vec::new(), String::new(), data_type, http_version)
+    } else if !request.is_empty() {
+        // if the request file is specified - parse request file
+        if args.is_present("verbatim") {
+            parse_request(&without_injection_lines(&request), &scheme, port, data_type, args.value_of("split-by"))?
         } else {
-            None
-        };
+            parse_request(&request, &scheme, port, data_type, args.value_of("split-by"))?
+        }
+    } else if !curl.is_empty() {
+        let (methods, urls, headers, body, data_type, curl_http_version) = parse_curl(&curl, data_type)?;
 
-        parse_request(&request, &scheme, port, data_type, args.value_of("split-by"))?
+        // --http has a priority against curl's --http2 and --http1.1
+        (methods, urls, headers, body, data_type, http_version.or(curl_http_version))
     } else {
         // parse everything from user-supplied command line arguments
         let methods = if args.is_present("method") {
             args.values_of("method")
                 .unwrap()
+                .iter()
                 .map(|x| x.to_string())
                 .collect::<Vec<String>>()
         } else {
             vec!["GET".to_string()]
         };
 
-        let mut headers: HashMap<&str, String> = HashMap::new();
-
-        if let Some(val) = args.values_of("headers") {
-            for header in val {
-                let mut k_v = header.split(':');
-                let key = match k_v.next() {
-                    Some(val) => val,
-                    None => Err("Unable to parse headers")?,
-                };
-                let value = [
-                    match k_v.next() {
-                        Some(val) => val.trim().to_owned(),
-                        None => Err("Unable to parse headers")?,
-                    },
-                    k_v.map(|x| ":".to_owned() + x).collect(),
-                ]
-                .concat();
-
-                headers.insert(key, value);
-            }
-        };
-
-        // set default headers if weren't specified by a user.
-        let headers = if args.is_present("mimic-browser") {
-            mimic_browser_headers(headers)
-        } else {
-            add_default_headers(headers)
-        };
-
         // TODO replace with ".parse()" or sth like it
         let data_type = match data_type {
             Some(val) => {
                 Some(val)
             }
-            None => if headers.get_value_case_insensitive("content-type") == Some("application/json".to_string()) {
+            None => if user_headers.get_value_case_insensitive("content-type") == Some("application/json".to_string()) {
                 Some(DataType::ProbablyJson)
             } else {
                 None
             },
         };
 
-        let http_version = if args.value_of("http").is_some() {
-            match  args.value_of("http").unwrap() {
-                "1.1" => Some(http::Version::HTTP_11),
-                "2" => Some(http::Version::HTTP_2),
-                _ => {
-                    writeln!(
-                        io::stdout(),
-                        "[#] Incorrect http version provided. The argument is ignored"
-                    ).ok();
-                    None
-                }
-            }
-        } else {
-            None
-        };
-
         let urls = args
             .values_of("url")
             .unwrap();
 
-        let urls = if urls.len() == 1 && !urls.clone().any(|x| x.contains("://")) {
+        let urls = if urls.len() == 1 && !urls.iter().any(|x| x.contains("://")) {
             // it can be a file
-            match read_urls_if_possible(urls.clone().next().unwrap())? {
+            match read_urls_if_possible(urls[0])? {
                 Some(urls) => urls,
                 None => Err("The provided --url value is neither url nor a filename.")?
             }
         } else {
-            urls.map(|x| x.to_string()).collect()
+            urls.iter().map(|x| x.to_string()).collect()
         };
 
         let urls = urls.iter().map(|x| Url::parse(x))
@@ -513,7 +776,7 @@ Increase the amount of workers to remove the error or use --force.")?;
                 urls.iter()
                     .map(|x| x.as_ref().unwrap().to_string())
                     .collect::<Vec<String>>(),
-                headers,
+                user_headers,
                 args.value_of("body").unwrap_or("").to_string(),
                 data_type,
                 http_version
@@ -523,31 +786,13 @@ Increase the amount of workers to remove the error or use --force.")?;
 
     // generate custom param values like admin=true
     let custom_keys: Vec<String> = match args.values_of("custom-parameters") {
-        Some(val) => val.map(|x| x.to_string()).collect(),
-        None => [
-            "admin",
-            "bot",
-            "captcha",
-            "debug",
-            "disable",
-            "encryption",
-            "env",
-            "show",
-            "sso",
-            "test",
-            "waf",
-        ]
-        .iter()
-        .map(|x| x.to_string())
-        .collect(),
+        Some(val) => val.iter().map(|x| x.to_string()).collect(),
+        None => DEFAULT_CUSTOM_PARAMETERS.iter().map(|x| x.to_string()).collect(),
     };
 
     let custom_values: Vec<String> = match args.values_of("custom-values") {
-        Some(val) => val.map(|x| x.to_string()).collect(),
-        None => ["1", "0", "false", "off", "null", "true", "yes", "no"]
-            .iter()
-            .map(|x| x.to_string())
-            .collect(),
+        Some(val) => val.iter().map(|x| x.to_string()).collect(),
+        None => DEFAULT_CUSTOM_VALUES.iter().map(|x| x.to_string()).collect(),
     };
 
     let mut custom_parameters: HashMap<String, Vec<String>> =
@@ -560,6 +805,26 @@ Increase the amount of workers to remove the error or use --force.")?;
         custom_parameters.insert(key.to_string(), values);
     }
 
+    let mutate = args.values_of("mutate").unwrap_or_default();
+    let mutate = |kind: &str| mutate.contains(&kind) || mutate.contains(&"all");
+
+    let mutations = Mutations {
+        camel: mutate("camel"),
+        snake: mutate("snake"),
+        kebab: mutate("kebab"),
+        plural: mutate("plural"),
+        prefixes: match args.values_of("prefixes") {
+            Some(val) => val.iter().map(|x| x.to_string()).collect(),
+            None if mutate("affixes") => vec!["_".to_string(), "is".to_string()],
+            None => Vec::new(),
+        },
+        suffixes: match args.values_of("suffixes") {
+            Some(val) => val.iter().map(|x| x.to_string()).collect(),
+            None if mutate("affixes") => vec!["Id".to_string(), "[]".to_string()],
+            None => Vec::new(),
+        },
+    };
+
     // disable colors
     if args.is_present("disable-colors") {
         colored::control::set_override(false);
@@ -584,19 +849,52 @@ Increase the amount of workers to remove the error or use --force.")?;
         args.value_of("proxy").unwrap_or("").to_string()
     };
 
-    if args.is_present("cookies") {
-        if let Some(index) = headers.get_index_case_insensitive("cookie") {
-            headers[index] = (headers[index].0.clone(), headers[index].1.clone()+";%s")
-        } else {
-            headers.push(("Cookie".to_string(), "%s".to_string()));
+    let mut targets = match imported_targets {
+        Some(targets) => targets,
+        None => {
+            let mut targets = Vec::with_capacity(urls.len() * methods.len());
+            for url in urls.iter() {
+                for method in methods.iter() {
+                    targets.push(Target {
+                        method: method.to_owned(),
+                        url: url.to_owned(),
+                        headers: headers.clone(),
+                        body: body.clone(),
+                        data_type: data_type.clone(),
+                        parameters: Vec::new(),
+                        raw_parameters: Vec::new(),
+                        invert: false,
+                        raw: None,
+                        http_version: None,
+                    });
+                }
+            }
+            targets
         }
+    };
+
+    // a single request file is sent as it is
+    if args.is_present("verbatim") && request_filenames.len() == 1 {
+        set_verbatim(&mut targets[0], &request, args.is_present("headers-discovery"))?;
+    }
+
+    if args.is_present("cookies") {
+        add_cookies_injection_point(&mut headers);
+        targets.iter_mut().for_each(|x| add_cookies_injection_point(&mut x.headers));
     }
 
     // TODO maybe replace empty with None
     Ok(Config {
         urls,
         methods,
-        wordlist: args.value_of("wordlist").unwrap_or("").to_string(),
+        targets,
+        wordlists: args
+            .values_of("wordlist")
+            .unwrap_or_default()
+            .iter()
+            .filter(|x| !x.is_empty())
+            .map(|x| WordlistFile::parse(x))
+            .collect(),
         custom_parameters,
         proxy,
         replay_proxy: args.value_of("replay-proxy").unwrap_or("").to_string(),
@@ -630,6 +928,9 @@ Increase the amount of workers to remove the error or use --force.")?;
         headers_discovery: args.is_present("headers-discovery") || args.is_present("cookies"),
         body,
         delay,
+        rate_limiter: Arc::new(rate_limiter),
+        retry_policy,
+        rate_limit_signatures,
         custom_headers: headers
             .iter()
             .map(|(k, v)| (k.to_string(), v.to_string()))
@@ -640,5 +941,12 @@ Increase the amount of workers to remove the error or use --force.")?;
         remove_banner: args.is_present("remove-banner"),
         disable_trustdns: args.is_present("disable-trustdns"),
         check_binary: args.is_present("check-binary"),
+        state_file: args.value_of("resume").or(args.value_of("state")).unwrap_or("").to_string(),
+        resume: args.is_present("resume"),
+        baseline: args.value_of("baseline").unwrap_or("").to_string(),
+        only_new: args.is_present("only-new"),
+        db: args.value_of("db").unwrap_or("").to_string(),
+        mine_scripts: args.is_present("mine-scripts"),
+        mutations,
     })
 }
@@ -0,0 +1,107 @@
+use std::{error::Error, fs};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use url::Url;
+
+use super::{
+    structs::Target,
+    utils::{parse_request, RequestFilter},
+};
+
+/// reads requests from burp's "Save items" xml export
+pub(super) fn read_burp_xml(
+    filename: &str,
+    filter: &RequestFilter,
+) -> Result<Vec<Target>, Box<dyn Error>> {
+    parse_burp_xml(&fs::read_to_string(filename)?, filter)
+}
+
+pub(super) fn parse_burp_xml(
+    content: &str,
+    filter: &RequestFilter,
+) -> Result<Vec<Target>, Box<dyn Error>> {
+    // burp adds the DOCTYPE declaration to every export
+    let options = roxmltree::ParsingOptions {
+        allow_dtd: true,
+        ..Default::default()
+    };
+
+    let document = match roxmltree::Document::parse_with_options(content, options) {
+        Ok(val) => val,
+        Err(err) => Err(format!("Unable to parse the burp xml file: {}", err))?,
+    };
+
+    let mut targets: Vec<Target> = Vec::new();
+
+    for item in document
+        .root_element()
+        .children()
+        .filter(|x| x.has_tag_name("item"))
+    {
+        let child = |name: &str| item.children().find(|x| x.has_tag_name(name));
+        let text = |name: &str| child(name).and_then(|x| x.text()).unwrap_or("").trim();
+
+        let request = match child("request") {
+            Some(val) => val,
+            None => continue,
+        };
+
+        let request = if request.attribute("base64") == Some("true") {
+            let decoded = STANDARD.decode(request.text().unwrap_or("").trim())?;
+            String::from_utf8_lossy(&decoded).to_string()
+        } else {
+            request.text().unwrap_or("").to_string()
+        };
+
+        if request.is_empty() {
+            continue;
+        }
+
+        let scheme = match text("protocol") {
+            "" => "https",
+            val => val,
+        };
+
+        let port: Option<u16> = match text("port") {
+            "" => None,
+            val => Some(val.parse()?),
+        };
+
+        let (methods, urls, headers, body, data_type, http_version) =
+            parse_request(&request, scheme, port, None, None)?;
+
+        // the url is created from the host header by default
+        // while the <host> element contains the real target
+        let mut url = Url::parse(&urls[0])?;
+        if !text("host").is_empty() {
+            url.set_host(Some(text("host")))?;
+        }
+
+        if !filter.matches(&url, Some(text("mimetype"))) {
+            continue;
+        }
+
+        let target = Target {
+            method: methods[0].to_owned(),
+            url: url.to_string(),
+            headers,
+            body,
+            data_type,
+            parameters: Vec::new(),
+            raw_parameters: Vec::new(),
+            invert: false,
+            raw: None,
+            http_version,
+        };
+
+        if !targets.iter().any(|x| x.is_same_request(&target)) {
+            targets.push(target);
+        }
+    }
+
+    if targets.is_empty() {
+        Err("No requests left in the burp xml file after filtering")?;
+    }
+
+    Ok(targets)
+}
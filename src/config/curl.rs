@@ -0,0 +1,315 @@
+use std::{error::Error, fs};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use url::Url;
+
+use crate::network::utils::{DataType, Headers};
+
+use super::utils::ParsedRequest;
+
+/// curl options with a value that don't affect the request
+const IGNORED_WITH_VALUE: [&str; 34] = [
+    "-o", "--output", "-x", "--proxy", "-U", "--proxy-user", "-m", "--max-time", "--connect-timeout",
+    "-w", "--write-out", "--retry", "--retry-delay", "--retry-max-time", "-c", "--cookie-jar", "-E",
+    "--cert", "--cert-type", "--key", "--key-type", "--cacert", "--capath", "--ciphers", "--resolve",
+    "--connect-to", "--limit-rate", "--max-redirs", "--interface", "--dns-servers", "-y", "--speed-time",
+    "-Y", "--speed-limit",
+];
+
+/// curl options without a value that don't affect the request
+const IGNORED_FLAGS: [&str; 24] = [
+    "-s", "--silent", "-S", "--show-error", "-k", "--insecure", "-i", "--include", "-L", "--location",
+    "-v", "--verbose", "-f", "--fail", "-g", "--globoff", "-N", "--no-buffer", "--path-as-is", "-#",
+    "--progress-bar", "--no-progress-meter", "--tr-encoding", "--ssl-no-revoke",
+];
+
+/// curl keeps only unreserved characters within --data-urlencode values
+const DATA_URLENCODE: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// parse the curl command (like the one from "Copy as cURL") into the same parts as the request file
+pub(super) fn parse_curl(
+    command: &str,
+    mut data_type: Option<DataType>,
+) -> Result<ParsedRequest, Box<dyn Error>> {
+    let args = split_command(command)?;
+    let mut args = args.iter().map(|x| x.as_str());
+
+    match args.next() {
+        Some("curl") => (),
+        _ => Err("The curl command should start with 'curl'")?,
+    }
+
+    let mut method = None;
+    let mut url = None;
+    let mut headers: Vec<(String, String)> = Vec::new();
+    let mut data: Vec<String> = Vec::new();
+    let mut cookies: Vec<String> = Vec::new();
+    let mut compressed = false;
+    let mut get = false;
+    let mut head = false;
+    let mut http_version = None;
+
+    while let Some(arg) = args.next() {
+        // -XPOST is also a valid form
+        let (arg, inline_value) =
+            if !arg.starts_with("--") && arg.starts_with('-') && arg.len() > 2 && arg.is_char_boundary(2) {
+                (&arg[..2], Some(&arg[2..]))
+            } else {
+                (arg, None)
+            };
+
+        let mut value = || -> Result<String, Box<dyn Error>> {
+            match inline_value.or_else(|| args.next()) {
+                Some(val) => Ok(val.to_string()),
+                None => Err(format!("No value provided for {}", arg))?,
+            }
+        };
+
+        match arg {
+            "-X" | "--request" => method = Some(value()?.to_uppercase()),
+            "-H" | "--header" => {
+                let header = value()?;
+                let (key, value) = header.split_once(':').ok_or("Unable to parse curl headers")?;
+
+                headers.push((key.trim().to_string(), value.trim().to_string()));
+            }
+            "--data-raw" => data.push(value()?),
+            // @filename reads the data from the file. Line breaks are removed unless it's --data-binary
+            "-d" | "--data" | "--data-ascii" | "--data-binary" => {
+                let value = value()?;
+                match value.strip_prefix('@') {
+                    Some(filename) if arg == "--data-binary" => data.push(read_data_file(filename)?),
+                    Some(filename) => data.push(read_data_file(filename)?.replace(['\r', '\n'], "")),
+                    None => data.push(value),
+                }
+            }
+            "--data-urlencode" => data.push(data_urlencode(&value()?)?),
+            "-b" | "--cookie" => {
+                let cookie = value()?;
+                // otherwise it's a file with cookies
+                if cookie.contains('=') {
+                    cookies.push(cookie)
+                }
+            }
+            "-A" | "--user-agent" => headers.push(("User-Agent".to_string(), value()?)),
+            "-e" | "--referer" => headers.push(("Referer".to_string(), value()?)),
+            "-u" | "--user" => headers.push((
+                "Authorization".to_string(),
+                format!("Basic {}", STANDARD.encode(value()?)),
+            )),
+            "--url" => url = Some(value()?),
+            "--compressed" => compressed = true,
+            "-G" | "--get" => get = true,
+            "-I" | "--head" => head = true,
+            "--http2" | "--http2-prior-knowledge" => http_version = Some(http::Version::HTTP_2),
+            "--http1.1" => http_version = Some(http::Version::HTTP_11),
+            _ if IGNORED_WITH_VALUE.contains(&arg) => {
+                value()?;
+            }
+            _ if IGNORED_FLAGS.contains(&arg) => (),
+            // the value of an unknown option would be taken as the url otherwise
+            _ if arg.starts_with('-') => Err(format!("Unsupported curl option: {}", arg))?,
+            _ => url = Some(arg.to_string()),
+        }
+    }
+
+    let url = url.ok_or("Unable to find the url within the curl command")?;
+
+    // curl uses http by default
+    let url = if url.contains("://") {
+        url
+    } else {
+        format!("http://{}", url)
+    };
+
+    let mut url = Url::parse(&url)?;
+
+    if !cookies.is_empty() {
+        headers.push(("Cookie".to_string(), cookies.join("; ")));
+    }
+
+    // reqwest decompresses responses only when it sets accept-encoding itself
+    if compressed {
+        headers.retain(|(k, _)| k.to_lowercase() != "accept-encoding");
+    }
+
+    // breaks h2 the same way as within the request file
+    headers.retain(|(k, _)| k.to_lowercase() != "content-length");
+
+    let mut body = data.join("&");
+
+    // -G moves the data to the query
+    if get && !body.is_empty() {
+        let query = match url.query() {
+            Some(val) => format!("{}&{}", val, body),
+            None => body,
+        };
+        url.set_query(Some(&query));
+        body = String::new();
+    }
+
+    let method = method.unwrap_or_else(|| {
+        if head {
+            "HEAD".to_string()
+        } else if body.is_empty() {
+            "GET".to_string()
+        } else {
+            "POST".to_string()
+        }
+    });
+
+    match headers.get_value_case_insensitive("content-type") {
+        Some(val) if val.contains("json") && data_type.is_none() => data_type = Some(DataType::ProbablyJson),
+        Some(_) => (),
+        // curl sends -d data with this content type by default
+        None if !body.is_empty() => headers.push((
+            "Content-Type".to_string(),
+            "application/x-www-form-urlencoded".to_string(),
+        )),
+        None => (),
+    }
+
+    Ok((
+        vec![method],
+        vec![url.to_string()],
+        headers,
+        body,
+        data_type,
+        http_version,
+    ))
+}
+
+/// encodes the value the same way as curl does: content, =content, name=content, @filename or name@filename
+fn data_urlencode(value: &str) -> Result<String, Box<dyn Error>> {
+    let encode = |x: &str| utf8_percent_encode(x, DATA_URLENCODE).to_string();
+
+    // the name can't contain '=' so the first one of '=' or '@' is the separator
+    match value.find(['=', '@']) {
+        Some(i) if value[i..].starts_with('=') => match &value[..i] {
+            "" => Ok(encode(&value[1..])),
+            name => Ok(format!("{}={}", name, encode(&value[i + 1..]))),
+        },
+        Some(i) => {
+            let content = read_data_file(&value[i + 1..])?;
+            match &value[..i] {
+                "" => Ok(encode(&content)),
+                name => Ok(format!("{}={}", name, encode(&content))),
+            }
+        }
+        None => Ok(encode(value)),
+    }
+}
+
+fn read_data_file(filename: &str) -> Result<String, Box<dyn Error>> {
+    match fs::read_to_string(filename) {
+        Ok(val) => Ok(val),
+        Err(err) => Err(format!("Unable to read {} from the curl command: {}", filename, err))?,
+    }
+}
+
+/// splits the command into arguments the same way as a posix shell does.
+/// Supports single and double quotes, $'..' strings, and line continuations
+fn split_command(command: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut args = Vec::new();
+    let mut arg = String::new();
+    // to distinguish between '' and no argument at all
+    let mut in_arg = false;
+    let mut chars = command.trim().chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' | '\t' | '\r' | '\n' => {
+                if in_arg {
+                    args.push(std::mem::take(&mut arg));
+                    in_arg = false;
+                }
+            }
+            '\\' => match chars.next() {
+                // line continuation
+                Some('\n') => (),
+                Some('\r') => {
+                    chars.next_if_eq(&'\n');
+                }
+                Some(c) => {
+                    arg.push(c);
+                    in_arg = true;
+                }
+                None => (),
+            },
+            '\'' => {
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => arg.push(c),
+                        None => Err("Unclosed quote within the curl command")?,
+                    }
+                }
+            }
+            '"' => {
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => arg.push(c),
+                            Some('\n') => (),
+                            Some(c) => {
+                                arg.push('\\');
+                                arg.push(c);
+                            }
+                            None => Err("Unclosed quote within the curl command")?,
+                        },
+                        Some(c) => arg.push(c),
+                        None => Err("Unclosed quote within the curl command")?,
+                    }
+                }
+            }
+            // browsers use $'..' for strings with special characters
+            '$' if chars.peek() == Some(&'\'') => {
+                chars.next();
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => arg.push('\n'),
+                            Some('r') => arg.push('\r'),
+                            Some('t') => arg.push('\t'),
+                            Some('x') => {
+                                let hex: String = (0..2).filter_map(|_| chars.next()).collect();
+                                arg.push(u8::from_str_radix(&hex, 16)? as char);
+                            }
+                            Some('u') => {
+                                let hex: String = (0..4).filter_map(|_| chars.next()).collect();
+                                arg.push(
+                                    char::from_u32(u32::from_str_radix(&hex, 16)?)
+                                        .ok_or("Wrong unicode escape within the curl command")?,
+                                );
+                            }
+                            Some(c) => arg.push(c),
+                            None => Err("Unclosed quote within the curl command")?,
+                        },
+                        Some(c) => arg.push(c),
+                        None => Err("Unclosed quote within the curl command")?,
+                    }
+                }
+            }
+            c => {
+                arg.push(c);
+                in_arg = true;
+            }
+        }
+    }
+
+    if in_arg {
+        args.push(arg);
+    }
+
+    Ok(args)
+}
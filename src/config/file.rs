@@ -0,0 +1,264 @@
+use std::{
+    collections::HashMap,
+    env,
+    error::Error,
+    fs,
+    path::PathBuf,
+};
+
+use clap::{App, ArgMatches, ErrorKind};
+
+/// config file in the current directory. Usually checked into the project's repo
+const LOCAL_CONFIG_FILE: &str = "x8.toml";
+
+/// command line arguments with fallback to the values from the config file
+pub(super) struct Args<'a> {
+    matches: ArgMatches<'a>,
+
+    /// argument name -> values
+    file: HashMap<String, Vec<String>>,
+}
+
+impl<'a> Args<'a> {
+    /// config file values that clap would reject together with the command line arguments are ignored.
+    /// For example, --har replaces url from the config file
+    pub fn new(
+        matches: ArgMatches<'a>,
+        mut file: HashMap<String, Vec<String>>,
+        app: &App<'static, 'static>,
+        arg_names: &[&str],
+    ) -> Self {
+        let provided: Vec<String> = arg_names
+            .iter()
+            .filter(|x| matches.occurrences_of(x) != 0)
+            .map(|x| match matches.value_of(x) {
+                Some(value) => format!("--{}={}", x, value),
+                None => format!("--{}", x),
+            })
+            .collect();
+
+        file.retain(|k, values| {
+            let file_argv = conflict_argv(k, values, app);
+
+            !provided.iter().any(|arg| {
+                let argv: Vec<String> = std::iter::once(arg.to_owned()).chain(file_argv.iter().cloned()).collect();
+                matches!(parse(app, &argv), Err(err) if err.kind == ErrorKind::ArgumentConflict)
+            })
+        });
+
+        Self { matches, file }
+    }
+
+    /// returns the config file values only in case the argument wasn't provided via the command line
+    fn file_value(&self, name: &str) -> Option<&Vec<String>> {
+        if self.matches.occurrences_of(name) == 0 {
+            self.file.get(name)
+        } else {
+            None
+        }
+    }
+
+    pub fn value_of(&self, name: &str) -> Option<&str> {
+        match self.file_value(name) {
+            Some(val) => val.first().map(|x| x.as_str()),
+            None => self.matches.value_of(name),
+        }
+    }
+
+    pub fn values_of(&self, name: &str) -> Option<Vec<&str>> {
+        match self.file_value(name) {
+            Some(val) => Some(val.iter().map(|x| x.as_str()).collect()),
+            None => self.matches.values_of(name).map(|x| x.collect()),
+        }
+    }
+
+    pub fn is_present(&self, name: &str) -> bool {
+        match self.file_value(name) {
+            Some(val) => val.first().map(|x| x != "false").unwrap_or(false),
+            None => self.matches.is_present(name),
+        }
+    }
+}
+
+/// reads the config file and returns argument values from the top level and the chosen profile.
+/// Every value is checked by the app, where arguments are named --<name> (see config_app())
+pub(super) fn read_config_file(
+    filename: Option<&str>,
+    profile: Option<&str>,
+    app: &App<'static, 'static>,
+) -> Result<HashMap<String, Vec<String>>, Box<dyn Error>> {
+    let filename = match filename {
+        Some(val) => PathBuf::from(val),
+        None => match default_location() {
+            Some(val) => val,
+            None => {
+                if profile.is_some() {
+                    Err("--profile is specified but no config file was found")?;
+                }
+                return Ok(HashMap::new());
+            }
+        },
+    };
+
+    let content = match fs::read_to_string(&filename) {
+        Ok(val) => val,
+        Err(err) => Err(format!("Unable to read {}: {}", filename.display(), err))?,
+    };
+
+    parse_config(&content, profile, app)
+}
+
+/// the config file is looked up in the current directory first and then in the user's config directory
+fn default_location() -> Option<PathBuf> {
+    let local = PathBuf::from(LOCAL_CONFIG_FILE);
+    if local.is_file() {
+        return Some(local);
+    }
+
+    let config_dir = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|x| PathBuf::from(x).join(".config")))
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))?;
+
+    let global = config_dir.join("x8").join("config.toml");
+    if global.is_file() {
+        Some(global)
+    } else {
+        None
+    }
+}
+
+/// top level keys are applied to every run, profile keys overwrite them.
+/// Every profile is checked, even the ones that aren't chosen
+pub(super) fn parse_config(
+    content: &str,
+    profile: Option<&str>,
+    app: &App<'static, 'static>,
+) -> Result<HashMap<String, Vec<String>>, Box<dyn Error>> {
+    let config: toml::Value = content.parse()?;
+    let config = config.as_table().ok_or("The config file should be a table")?;
+
+    let mut values = table_values(config, app)?;
+
+    let profiles = match config.get("profile") {
+        Some(toml::Value::Table(val)) => val.to_owned(),
+        Some(_) => Err("profile should be a table of profiles")?,
+        None => Default::default(),
+    };
+
+    let mut profile_values = HashMap::new();
+    for (name, profile_table) in profiles.iter() {
+        let profile_table = profile_table
+            .as_table()
+            .ok_or(format!("Profile {} should be a table", name))?;
+
+        profile_values.insert(name.to_owned(), table_values(profile_table, app)?);
+    }
+
+    if let Some(profile) = profile {
+        match profile_values.remove(profile) {
+            Some(val) => values.extend(val),
+            None => Err(format!("Profile {} not found in the config file", profile))?,
+        }
+    }
+
+    Ok(values)
+}
+
+/// converts and checks values of the top level or a profile
+fn table_values(
+    table: &toml::value::Table,
+    app: &App<'static, 'static>,
+) -> Result<HashMap<String, Vec<String>>, Box<dyn Error>> {
+    let mut values = HashMap::new();
+
+    for (k, v) in table.iter() {
+        if k == "profile" {
+            continue;
+        }
+
+        let value = toml_to_values(k, v)?;
+        check_value(k, &value, app)?;
+        values.insert(k.to_owned(), value);
+    }
+
+    Ok(values)
+}
+
+/// misspelled keys would be silently ignored otherwise, while invalid values would be noticed only once used
+fn check_value(key: &str, values: &[String], app: &App<'static, 'static>) -> Result<(), Box<dyn Error>> {
+    let flag = parse(app, &[format!("--{}", key)]);
+
+    if key == "config" || key == "profile" || matches!(&flag, Err(err) if err.kind == ErrorKind::UnknownArgument) {
+        Err(format!("Unknown key in the config file: {}", key))?;
+    }
+
+    let argv: Vec<String> = if is_flag(key, &flag) {
+        match values {
+            [value] if value == "true" || value == "false" => return Ok(()),
+            _ => Err(format!("Invalid value of {} in the config file: flags take true or false", key))?,
+        }
+    } else {
+        std::iter::once(format!("--{}", key)).chain(values.iter().cloned()).collect()
+    };
+
+    match parse(app, &argv) {
+        // requirements like --proto needing --request can be satisfied via the command line
+        Ok(_) => Ok(()),
+        Err(err) if err.kind == ErrorKind::MissingRequiredArgument => Ok(()),
+        Err(err) => Err(format!(
+            "Invalid value of {} in the config file: {}",
+            key,
+            err.message.lines().next().unwrap_or_default().trim_start_matches("error: ")
+        ))?,
+    }
+}
+
+/// options either fail without a value or get the default one
+fn is_flag(key: &str, result: &clap::Result<ArgMatches<'static>>) -> bool {
+    match result {
+        Ok(matches) => matches.value_of(key).is_none(),
+        Err(err) => err.kind != ErrorKind::EmptyValue,
+    }
+}
+
+/// the argument with a single value to check whether it conflicts with other arguments.
+/// The --name=value form ends the list of values
+fn conflict_argv(key: &str, values: &[String], app: &App<'static, 'static>) -> Vec<String> {
+    if is_flag(key, &parse(app, &[format!("--{}", key)])) {
+        match values.first() {
+            Some(value) if value == "false" => Vec::new(),
+            _ => vec![format!("--{}", key)],
+        }
+    } else {
+        values.iter().take(1).map(|x| format!("--{}={}", key, x)).collect()
+    }
+}
+
+fn parse(app: &App<'static, 'static>, argv: &[String]) -> clap::Result<ArgMatches<'static>> {
+    app.clone()
+        .get_matches_from_safe(std::iter::once("x8".to_string()).chain(argv.iter().cloned()))
+}
+
+/// converts the value to the same form as clap returns it
+fn toml_to_values(key: &str, value: &toml::Value) -> Result<Vec<String>, Box<dyn Error>> {
+    match value {
+        toml::Value::String(val) => Ok(vec![val.to_owned()]),
+        toml::Value::Integer(val) => Ok(vec![val.to_string()]),
+        toml::Value::Float(val) => Ok(vec![val.to_string()]),
+        toml::Value::Boolean(val) => Ok(vec![val.to_string()]),
+        toml::Value::Array(val) => {
+            let mut values = Vec::with_capacity(val.len());
+            for el in val {
+                match el {
+                    toml::Value::Array(_) | toml::Value::Table(_) => {
+                        Err(format!("Nested values are not supported ({})", key))?
+                    }
+                    _ => values.append(&mut toml_to_values(key, el)?),
+                }
+            }
+            Ok(values)
+        }
+        _ => Err(format!("Unsupported value type for {}", key))?,
+    }
+}
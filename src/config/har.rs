@@ -0,0 +1,176 @@
+use std::{error::Error, fs};
+
+use serde::Deserialize;
+use url::Url;
+
+use crate::network::utils::{DataType, Headers};
+
+use super::{structs::Target, utils::RequestFilter};
+
+#[derive(Debug, Deserialize)]
+struct Har {
+    log: HarLog,
+}
+
+#[derive(Debug, Deserialize)]
+struct HarLog {
+    #[serde(default)]
+    entries: Vec<HarEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HarEntry {
+    request: HarRequest,
+    response: Option<HarResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HarRequest {
+    method: String,
+    url: String,
+    #[serde(default)]
+    http_version: String,
+    #[serde(default)]
+    headers: Vec<HarPair>,
+    post_data: Option<HarPostData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HarPair {
+    name: String,
+    #[serde(default)]
+    value: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HarPostData {
+    #[serde(default)]
+    mime_type: String,
+    text: Option<String>,
+    #[serde(default)]
+    params: Vec<HarPair>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HarResponse {
+    content: Option<HarContent>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HarContent {
+    mime_type: Option<String>,
+}
+
+/// reads every entry from the har file and turns it into a target
+pub(super) fn read_har(
+    filename: &str,
+    filter: &RequestFilter,
+) -> Result<Vec<Target>, Box<dyn Error>> {
+    parse_har(&fs::read_to_string(filename)?, filter)
+}
+
+pub(super) fn parse_har(
+    content: &str,
+    filter: &RequestFilter,
+) -> Result<Vec<Target>, Box<dyn Error>> {
+    let har: Har = match serde_json::from_str(content) {
+        Ok(val) => val,
+        Err(err) => Err(format!("Unable to parse the har file: {}", err))?,
+    };
+
+    let mut targets: Vec<Target> = Vec::new();
+
+    for entry in har.log.entries {
+        let request = entry.request;
+
+        // skip data:, ws:, chrome-extension: and other non http entries
+        let url = match Url::parse(&request.url) {
+            Ok(val) if val.scheme() == "http" || val.scheme() == "https" => val,
+            _ => continue,
+        };
+
+        let mime_type = entry
+            .response
+            .and_then(|x| x.content)
+            .and_then(|x| x.mime_type);
+
+        if !filter.matches(&url, mime_type.as_deref()) {
+            continue;
+        }
+
+        let http2 = request.http_version.contains('2');
+
+        let headers: Vec<(String, String)> = request
+            .headers
+            .into_iter()
+            // http/2 pseudo headers like :authority
+            .filter(|x| !x.name.starts_with(':'))
+            .filter(|x| match x.name.to_lowercase().as_str() {
+                // the same as within the request file
+                "content-length" => false,
+                "host" => !http2,
+                _ => true,
+            })
+            .map(|x| (x.name, x.value))
+            .collect();
+
+        let content_type = match headers.get_value_case_insensitive("content-type") {
+            Some(val) => val,
+            None => request
+                .post_data
+                .as_ref()
+                .map(|x| x.mime_type.to_owned())
+                .unwrap_or_default(),
+        };
+
+        let data_type = if content_type.contains("json") {
+            Some(DataType::ProbablyJson)
+        } else {
+            None
+        };
+
+        let body = match request.post_data {
+            Some(post_data) => match post_data.text {
+                Some(text) => text,
+                None => post_data
+                    .params
+                    .iter()
+                    .map(|x| format!("{}={}", x.name, x.value))
+                    .collect::<Vec<String>>()
+                    .join("&"),
+            },
+            None => String::new(),
+        };
+
+        let target = Target {
+            method: request.method,
+            url: url.to_string(),
+            headers,
+            body,
+            data_type,
+            parameters: Vec::new(),
+            raw_parameters: Vec::new(),
+            invert: false,
+            raw: None,
+            http_version: match request.http_version.as_str() {
+                "" => None,
+                _ if http2 => Some(http::Version::HTTP_2),
+                _ => Some(http::Version::HTTP_11),
+            },
+        };
+
+        // browsers usually make the same request multiple times
+        if !targets.iter().any(|x| x.is_same_request(&target)) {
+            targets.push(target);
+        }
+    }
+
+    if targets.is_empty() {
+        Err("No requests left in the har file after filtering")?;
+    }
+
+    Ok(targets)
+}
@@ -1,3 +1,14 @@
 pub mod args;
+pub mod burp;
+pub mod curl;
+pub mod file;
+pub mod har;
+pub mod mutations;
+pub mod openapi;
+pub mod postman;
 pub mod structs;
 pub mod utils;
+pub mod wordlist;
+
+#[allow(clippy::module_inception)]
+mod tests;
@@ -0,0 +1,231 @@
+use std::collections::HashSet;
+
+/// generates variants of wordlist entries
+#[derive(Debug, Clone, Default)]
+pub struct Mutations {
+    /// userName
+    pub camel: bool,
+
+    /// user_name
+    pub snake: bool,
+
+    /// user-name
+    pub kebab: bool,
+
+    /// user -> users, users -> user
+    pub plural: bool,
+
+    /// _user, isUser
+    pub prefixes: Vec<String>,
+
+    /// userId, user[]
+    pub suffixes: Vec<String>,
+}
+
+impl Mutations {
+    pub fn is_empty(&self) -> bool {
+        !(self.camel || self.snake || self.kebab || self.plural)
+            && self.prefixes.is_empty()
+            && self.suffixes.is_empty()
+    }
+
+    /// returns the original words followed by the generated variants.
+    /// The order is preserved and duplicates are removed
+    pub fn apply(&self, words: Vec<String>) -> Vec<String> {
+        if self.is_empty() {
+            return words;
+        }
+
+        let mut seen: HashSet<String> = words.iter().cloned().collect();
+        let mut generated = Vec::new();
+
+        for word in words.iter() {
+            for variant in self.variants(word) {
+                if !seen.contains(&variant) {
+                    seen.insert(variant.clone());
+                    generated.push(variant);
+                }
+            }
+        }
+
+        let mut words = words;
+        words.append(&mut generated);
+        words
+    }
+
+    fn variants(&self, word: &str) -> Vec<String> {
+        let tokens = split_word(word);
+        if tokens.is_empty() {
+            return Vec::new();
+        }
+
+        let mut variants = vec![word.to_string()];
+
+        // only words with multiple parts have case variants
+        if tokens.len() > 1 {
+            if self.camel {
+                variants.push(join(&tokens, Case::Camel));
+            }
+            if self.snake {
+                variants.push(join(&tokens, Case::Snake));
+            }
+            if self.kebab {
+                variants.push(join(&tokens, Case::Kebab));
+            }
+        }
+
+        if self.plural {
+            for i in 0..variants.len() {
+                variants.push(toggle_plural(&variants[i]));
+            }
+        }
+
+        for i in 0..variants.len() {
+            let case = Case::of(&variants[i]);
+
+            for prefix in self.prefixes.iter() {
+                variants.push(add_affix(&variants[i], prefix, case, true));
+            }
+            for suffix in self.suffixes.iter() {
+                variants.push(add_affix(&variants[i], suffix, case, false));
+            }
+        }
+
+        // the word itself
+        variants.remove(0);
+        variants
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Case {
+    Camel,
+    Snake,
+    Kebab,
+}
+
+impl Case {
+    fn of(word: &str) -> Self {
+        if word.contains('_') {
+            Case::Snake
+        } else if word.contains('-') {
+            Case::Kebab
+        } else {
+            Case::Camel
+        }
+    }
+}
+
+/// userName, user_name, user-name -> [user, name]
+fn split_word(word: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut prev_lowercase = false;
+
+    for c in word.chars() {
+        if c == '_' || c == '-' {
+            if !token.is_empty() {
+                tokens.push(std::mem::take(&mut token));
+            }
+            prev_lowercase = false;
+            continue;
+        }
+
+        if c.is_uppercase() && prev_lowercase && !token.is_empty() {
+            tokens.push(std::mem::take(&mut token));
+        }
+
+        prev_lowercase = c.is_lowercase() || c.is_ascii_digit();
+        token.extend(c.to_lowercase());
+    }
+
+    if !token.is_empty() {
+        tokens.push(token);
+    }
+
+    tokens
+}
+
+fn join(tokens: &[String], case: Case) -> String {
+    match case {
+        Case::Camel => tokens
+            .iter()
+            .enumerate()
+            .map(|(i, x)| if i == 0 { x.to_owned() } else { capitalize(x) })
+            .collect(),
+        Case::Snake => tokens.join("_"),
+        Case::Kebab => tokens.join("-"),
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(c) => c.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// affixes like "is" and "Id" are joined according to the word's case (isAdmin, is_admin)
+/// while the others (_, []) are added as they are
+fn add_affix(word: &str, affix: &str, case: Case, prefix: bool) -> String {
+    if !affix.chars().all(|x| x.is_alphanumeric()) {
+        return if prefix {
+            [affix, word].concat()
+        } else {
+            [word, affix].concat()
+        };
+    }
+
+    let affix = affix.to_lowercase();
+    let tokens = if prefix {
+        [vec![affix], split_word(word)].concat()
+    } else {
+        [split_word(word), vec![affix]].concat()
+    };
+
+    join(&tokens, case)
+}
+
+/// user -> users, category -> categories, users -> user
+fn toggle_plural(word: &str) -> String {
+    // suffixes are removed by their byte length
+    if !word.is_ascii() {
+        return word.to_string();
+    }
+
+    let lowercase = word.to_lowercase();
+    let is_vowel = |c: Option<char>| matches!(c, Some('a' | 'e' | 'i' | 'o' | 'u'));
+
+    if let Some(stem) = lowercase.strip_suffix("ies") {
+        if !stem.is_empty() {
+            return [&word[..word.len() - 3], "y"].concat();
+        }
+    }
+
+    for suffix in ["sses", "xes", "zes", "ches", "shes"] {
+        if lowercase.ends_with(suffix) {
+            return word[..word.len() - 2].to_string();
+        }
+    }
+
+    // status, analysis
+    if lowercase.ends_with('s')
+        && !["ss", "us", "is"].iter().any(|x| lowercase.ends_with(x))
+        && lowercase.len() > 1
+    {
+        return word[..word.len() - 1].to_string();
+    }
+
+    if lowercase.ends_with('y') && !is_vowel(lowercase.chars().rev().nth(1)) && lowercase.len() > 1 {
+        return [&word[..word.len() - 1], "ies"].concat();
+    }
+
+    for suffix in ["s", "x", "z", "ch", "sh"] {
+        if lowercase.ends_with(suffix) {
+            return [word, "es"].concat();
+        }
+    }
+
+    [word, "s"].concat()
+}
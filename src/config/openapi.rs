@@ -0,0 +1,345 @@
+use std::{error::Error, fs};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::Value;
+use url::Url;
+
+use crate::network::utils::{is_body_method, DataType};
+
+use super::{structs::Target, utils::RequestFilter};
+
+const METHODS: [&str; 7] = ["get", "post", "put", "patch", "delete", "head", "options"];
+
+/// to prevent infinite loops in case of circular references
+const MAX_REF_DEPTH: usize = 16;
+
+/// creates a target for every operation from the openapi (or swagger) json specification
+pub(super) fn read_openapi(
+    filename: &str,
+    base_url: Option<&str>,
+    headers: &[(String, String)],
+    filter: &RequestFilter,
+) -> Result<Vec<Target>, Box<dyn Error>> {
+    parse_openapi(&fs::read_to_string(filename)?, base_url, headers, filter)
+}
+
+pub(super) fn parse_openapi(
+    content: &str,
+    base_url: Option<&str>,
+    headers: &[(String, String)],
+    filter: &RequestFilter,
+) -> Result<Vec<Target>, Box<dyn Error>> {
+    lazy_static! {
+        static ref RE_PATH_PARAMETER: Regex = Regex::new(r"\{[^}/]*\}").unwrap();
+    }
+
+    let spec: Value = match serde_json::from_str(content) {
+        Ok(val) => val,
+        Err(err) => Err(format!("Unable to parse the openapi file: {}", err))?,
+    };
+
+    let base_url = match base_url {
+        Some(val) => val.to_string(),
+        None => spec_base_url(&spec)?,
+    };
+    let base_url = base_url.trim_end_matches('/');
+
+    let paths = spec
+        .get("paths")
+        .and_then(|x| x.as_object())
+        .ok_or("No paths found in the openapi file")?;
+
+    let mut targets = Vec::new();
+
+    for (path, path_item) in paths {
+        let path_item = resolve(&spec, path_item);
+
+        for method in METHODS {
+            let operation = match path_item.get(method) {
+                Some(val) => resolve(&spec, val),
+                None => continue,
+            };
+
+            // operation level parameters overwrite path level ones with the same name and location
+            let mut parameters: Vec<&Value> = Vec::new();
+            for parameter in operation
+                .get("parameters")
+                .and_then(|x| x.as_array())
+                .into_iter()
+                .chain(path_item.get("parameters").and_then(|x| x.as_array()))
+                .flatten()
+            {
+                let parameter = resolve(&spec, parameter);
+                if !parameters
+                    .iter()
+                    .any(|x| x.get("name") == parameter.get("name") && x.get("in") == parameter.get("in"))
+                {
+                    parameters.push(parameter);
+                }
+            }
+
+            let mut path = path.to_owned();
+            let mut query_parameters = Vec::new();
+            let mut body_parameters = Vec::new();
+            let mut headers = headers.to_vec();
+            let mut data_type = None;
+            let mut raw_parameters: Vec<String> = Vec::new();
+
+            for parameter in parameters {
+                let name = match parameter.get("name").and_then(|x| x.as_str()) {
+                    Some(val) => val,
+                    None => continue,
+                };
+
+                let value = example_value(&spec, parameter, 0);
+
+                match parameter.get("in").and_then(|x| x.as_str()).unwrap_or("") {
+                    "path" => {
+                        let value = value.as_ref().and_then(value_to_string).unwrap_or_else(|| "1".to_string());
+                        path = path.replace(&format!("{{{}}}", name), &value);
+                    }
+                    "query" => {
+                        if let Some(value) = value.as_ref().and_then(value_to_string) {
+                            query_parameters.push((name.to_string(), value));
+                        }
+                    }
+                    "header" => {
+                        if let Some(value) = value.as_ref().and_then(value_to_string) {
+                            headers.push((name.to_string(), value));
+                        }
+                    }
+                    // swagger 2.0
+                    "formData" => {
+                        data_type = Some(DataType::Urlencoded);
+                        if let Some(value) = value.as_ref().and_then(value_to_string) {
+                            body_parameters.push((name.to_string(), value));
+                        }
+                    }
+                    "body" => {
+                        data_type = Some(DataType::Json);
+                        if let Some(schema) = parameter.get("schema") {
+                            body_parameters.append(&mut schema_properties(&spec, schema, Some(&mut raw_parameters)));
+                        }
+                    }
+                    _ => (),
+                }
+            }
+
+            // openapi 3
+            if let Some(content) = operation
+                .get("requestBody")
+                .map(|x| resolve(&spec, x))
+                .and_then(|x| x.get("content"))
+                .and_then(|x| x.as_object())
+            {
+                if let Some((_, media_type)) = content.iter().find(|(k, _)| k.contains("json")) {
+                    data_type = Some(DataType::Json);
+                    if let Some(schema) = media_type.get("schema") {
+                        body_parameters.append(&mut schema_properties(&spec, schema, Some(&mut raw_parameters)));
+                    }
+                } else if let Some(media_type) = content.get("application/x-www-form-urlencoded") {
+                    data_type = Some(DataType::Urlencoded);
+                    if let Some(schema) = media_type.get("schema") {
+                        body_parameters.append(&mut schema_properties(&spec, schema, None));
+                    }
+                }
+            }
+
+            // undocumented path parameters
+            let path = RE_PATH_PARAMETER.replace_all(&path, "1").to_string();
+            let method = method.to_uppercase();
+
+            // parameters are sent within the body only in case the operation has a json or urlencoded body
+            // otherwise documented query parameters are the place
+            let (url, parameters) = if data_type.is_some() {
+                let query = query_parameters
+                    .iter()
+                    .map(|(k, v)| format!("{}={}", k, v))
+                    .collect::<Vec<String>>()
+                    .join("&");
+
+                if query.is_empty() {
+                    (format!("{}{}", base_url, path), body_parameters)
+                } else {
+                    (format!("{}{}?{}", base_url, path, query), body_parameters)
+                }
+            } else {
+                (format!("{}{}", base_url, path), query_parameters)
+            };
+
+            let url = match Url::parse(&url) {
+                Ok(val) => val,
+                Err(err) => Err(format!("Unable to create url for {} {}: {}", method, path, err))?,
+            };
+
+            if !filter.matches(&url, None) {
+                continue;
+            }
+
+            targets.push(Target {
+                invert: data_type.is_some() != is_body_method(&method),
+                method,
+                url: url.to_string(),
+                headers,
+                body: String::new(),
+                data_type,
+                parameters,
+                raw_parameters,
+                raw: None,
+                http_version: None,
+            });
+        }
+    }
+
+    if targets.is_empty() {
+        Err("No operations left in the openapi file after filtering")?;
+    }
+
+    Ok(targets)
+}
+
+/// servers[0].url for openapi 3 and schemes + host + basePath for swagger 2.0
+fn spec_base_url(spec: &Value) -> Result<String, Box<dyn Error>> {
+    let base_url = if let Some(url) = spec
+        .get("servers")
+        .and_then(|x| x.get(0))
+        .and_then(|x| x.get("url"))
+        .and_then(|x| x.as_str())
+    {
+        url.to_string()
+    } else if let Some(host) = spec.get("host").and_then(|x| x.as_str()) {
+        let scheme = spec
+            .get("schemes")
+            .and_then(|x| x.get(0))
+            .and_then(|x| x.as_str())
+            .unwrap_or("https");
+
+        format!(
+            "{}://{}{}",
+            scheme,
+            host,
+            spec.get("basePath").and_then(|x| x.as_str()).unwrap_or("")
+        )
+    } else {
+        String::new()
+    };
+
+    // relative server urls like /api/v1 need the base url as well
+    if !base_url.contains("://") {
+        Err("Unable to get the server url from the openapi file. Use --base-url")?;
+    }
+
+    Ok(base_url)
+}
+
+/// follows local references like #/components/schemas/User
+fn resolve<'a>(spec: &'a Value, mut value: &'a Value) -> &'a Value {
+    for _ in 0..MAX_REF_DEPTH {
+        match value
+            .get("$ref")
+            .and_then(|x| x.as_str())
+            .and_then(|x| x.strip_prefix('#'))
+            .and_then(|x| spec.pointer(x))
+        {
+            Some(val) => value = val,
+            None => break,
+        }
+    }
+
+    value
+}
+
+/// returns top level properties of an object schema with their example values
+/// nested objects and arrays are kept as json only in case raw_parameters is provided.
+/// Names of such properties are added to raw_parameters
+fn schema_properties(
+    spec: &Value,
+    schema: &Value,
+    mut raw_parameters: Option<&mut Vec<String>>,
+) -> Vec<(String, String)> {
+    let schema = resolve(spec, schema);
+    let mut properties = Vec::new();
+
+    if let Some(object) = schema.get("properties").and_then(|x| x.as_object()) {
+        for (name, property) in object {
+            let value = match example_value(spec, property, 0) {
+                Some(val) => val,
+                None => continue,
+            };
+
+            let value = match (value, raw_parameters.as_deref_mut()) {
+                (value @ (Value::Object(_) | Value::Array(_)), Some(raw_parameters)) => {
+                    raw_parameters.push(name.to_owned());
+                    Some(value.to_string())
+                }
+                (value, _) => value_to_string(&value),
+            };
+
+            if let Some(value) = value {
+                properties.push((name.to_owned(), value));
+            }
+        }
+    }
+
+    // allOf is often used for inherited schemas
+    for schema in schema.get("allOf").and_then(|x| x.as_array()).into_iter().flatten() {
+        properties.append(&mut schema_properties(spec, schema, raw_parameters.as_deref_mut()));
+    }
+
+    properties
+}
+
+/// the documented example or a value based on the parameter's type.
+/// Depth limits nested arrays, so recursive schemas like Node {items: {$ref: Node}} end with an empty array
+fn example_value(spec: &Value, parameter: &Value, depth: usize) -> Option<Value> {
+    let parameter = resolve(spec, parameter);
+    let schema = parameter.get("schema").map(|x| resolve(spec, x)).unwrap_or(parameter);
+
+    if let Some(example) = parameter.get("example").or_else(|| schema.get("example")) {
+        return Some(example.to_owned());
+    }
+
+    // openapi 3 examples map: {"name": {"value": ..}}
+    if let Some(example) = parameter
+        .get("examples")
+        .and_then(|x| x.as_object())
+        .and_then(|x| x.values().next())
+        .and_then(|x| x.get("value"))
+    {
+        return Some(example.to_owned());
+    }
+
+    if let Some(default) = schema.get("default") {
+        return Some(default.to_owned());
+    }
+
+    if let Some(first) = schema.get("enum").and_then(|x| x.get(0)) {
+        return Some(first.to_owned());
+    }
+
+    match schema.get("type").and_then(|x| x.as_str()).unwrap_or("string") {
+        "integer" | "number" => Some(Value::from(1)),
+        "boolean" => Some(Value::from(true)),
+        "array" => Some(Value::Array(
+            schema
+                .get("items")
+                .filter(|_| depth < MAX_REF_DEPTH)
+                .and_then(|x| example_value(spec, x, depth + 1))
+                .into_iter()
+                .collect(),
+        )),
+        "object" => Some(Value::Object(Default::default())),
+        _ => Some(Value::from("test")),
+    }
+}
+
+/// arrays are represented by their first element, objects are skipped
+fn value_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(val) => Some(val.to_owned()),
+        Value::Number(_) | Value::Bool(_) | Value::Null => Some(value.to_string()),
+        Value::Array(val) => val.first().and_then(value_to_string),
+        Value::Object(_) => None,
+    }
+}
@@ -0,0 +1,307 @@
+use std::{collections::HashMap, error::Error, fs};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use serde_json::Value;
+use url::{form_urlencoded, Url};
+
+use crate::network::utils::{is_body_method, DataType, Headers};
+
+use super::{structs::Target, utils::RequestFilter};
+
+/// variables can reference other variables
+const MAX_VARIABLE_DEPTH: usize = 8;
+
+/// reads every request from the postman v2.1 collection and turns it into a target
+pub(super) fn read_postman(
+    filename: &str,
+    environment: Option<&str>,
+    filter: &RequestFilter,
+) -> Result<Vec<Target>, Box<dyn Error>> {
+    let environment = match environment {
+        Some(val) => Some(fs::read_to_string(val)?),
+        None => None,
+    };
+
+    parse_postman(&fs::read_to_string(filename)?, environment.as_deref(), filter)
+}
+
+pub(super) fn parse_postman(
+    content: &str,
+    environment: Option<&str>,
+    filter: &RequestFilter,
+) -> Result<Vec<Target>, Box<dyn Error>> {
+    let collection: Value = match serde_json::from_str(content) {
+        Ok(val) => val,
+        Err(err) => Err(format!("Unable to parse the postman collection: {}", err))?,
+    };
+
+    // environment values have a priority against collection and folder variables
+    let environment = match environment {
+        Some(environment) => {
+            let environment: Value = match serde_json::from_str(environment) {
+                Ok(val) => val,
+                Err(err) => Err(format!("Unable to parse the postman environment: {}", err))?,
+            };
+
+            key_values(environment.get("values"))
+        }
+        None => HashMap::new(),
+    };
+
+    let mut requests = Vec::new();
+    collect_requests(&collection, None, &HashMap::new(), &mut requests);
+
+    let mut targets: Vec<Target> = Vec::new();
+
+    for (request, auth, mut variables) in requests {
+        variables.extend(environment.clone());
+
+        let target = match request_to_target(request, auth, &variables)? {
+            Some(val) => val,
+            None => continue,
+        };
+
+        if !filter.matches(&Url::parse(&target.url)?, None) {
+            continue;
+        }
+
+        if !targets.iter().any(|x| x.is_same_request(&target)) {
+            targets.push(target);
+        }
+    }
+
+    if targets.is_empty() {
+        Err("No requests left in the postman collection after filtering")?;
+    }
+
+    Ok(targets)
+}
+
+/// walks through folders and returns requests with the closest auth (folder or collection level)
+/// and variables of the collection and every parent folder. Closer variables have a priority
+fn collect_requests<'a>(
+    item: &'a Value,
+    auth: Option<&'a Value>,
+    variables: &HashMap<String, String>,
+    requests: &mut Vec<(&'a Value, Option<&'a Value>, HashMap<String, String>)>,
+) {
+    let auth = item.get("auth").or(auth);
+
+    let mut variables = variables.clone();
+    variables.extend(key_values(item.get("variable")));
+
+    if let Some(request) = item.get("request") {
+        requests.push((request, request.get("auth").or(auth), variables.clone()));
+    }
+
+    for child in item.get("item").and_then(|x| x.as_array()).into_iter().flatten() {
+        collect_requests(child, auth, &variables, requests);
+    }
+}
+
+fn request_to_target(
+    request: &Value,
+    auth: Option<&Value>,
+    variables: &HashMap<String, String>,
+) -> Result<Option<Target>, Box<dyn Error>> {
+    let resolve = |x: &str| resolve_variables(x, variables);
+
+    let method = resolve(request.get("method").and_then(|x| x.as_str()).unwrap_or("GET")).to_uppercase();
+
+    // the url is either a string or an object with the raw field
+    let url = match request.get("url") {
+        Some(Value::String(val)) => val.as_str(),
+        Some(val) => val.get("raw").and_then(|x| x.as_str()).unwrap_or(""),
+        None => "",
+    };
+
+    if url.is_empty() {
+        return Ok(None);
+    }
+
+    let url = resolve(url);
+
+    // postman allows urls without a scheme
+    let url = if url.contains("://") {
+        url
+    } else {
+        format!("https://{}", url)
+    };
+
+    let mut url = match Url::parse(&url) {
+        Ok(val) => val,
+        Err(err) => Err(format!("Unable to parse {}: {}", url, err))?,
+    };
+
+    let mut headers: Vec<(String, String)> = key_values_ordered(request.get("header"))
+        .into_iter()
+        .map(|(k, v)| (resolve(&k), resolve(&v)))
+        .filter(|(k, _)| k.to_lowercase() != "content-length")
+        .collect();
+
+    if let Some(auth) = auth {
+        add_auth(auth, &mut headers, &mut url, &resolve);
+    }
+
+    let body = request.get("body");
+    let (body, data_type) = match body.and_then(|x| x.get("mode")).and_then(|x| x.as_str()) {
+        Some("raw") => {
+            let raw = resolve(body.unwrap().get("raw").and_then(|x| x.as_str()).unwrap_or(""));
+
+            let language = body
+                .unwrap()
+                .pointer("/options/raw/language")
+                .and_then(|x| x.as_str())
+                .unwrap_or("");
+
+            let content_type = headers
+                .get_value_case_insensitive("content-type")
+                .unwrap_or_default();
+
+            // trimmed because the last '}' is replaced with the injection point
+            let raw = raw.trim().to_string();
+
+            if language == "json" || content_type.contains("json") {
+                if content_type.is_empty() {
+                    headers.push(("Content-Type".to_string(), "application/json".to_string()));
+                }
+                (raw, Some(DataType::Json))
+            } else if content_type.contains("x-www-form-urlencoded") {
+                (raw, Some(DataType::Urlencoded))
+            } else {
+                (raw, None)
+            }
+        }
+        // multipart bodies aren't supported so text fields of formdata are sent urlencoded
+        Some(mode @ "urlencoded") | Some(mode @ "formdata") => {
+            let body = form_urlencoded::Serializer::new(String::new())
+                .extend_pairs(
+                    key_values_ordered(body.unwrap().get(mode))
+                        .into_iter()
+                        .map(|(k, v)| (resolve(&k), resolve(&v))),
+                )
+                .finish();
+
+            headers.retain(|(k, _)| k.to_lowercase() != "content-type");
+            headers.push((
+                "Content-Type".to_string(),
+                "application/x-www-form-urlencoded".to_string(),
+            ));
+
+            (body, Some(DataType::Urlencoded))
+        }
+        _ => (String::new(), None),
+    };
+
+    Ok(Some(Target {
+        // requests with a body are always checked within the body
+        invert: data_type.is_some() && !is_body_method(&method),
+        method,
+        url: url.to_string(),
+        headers,
+        body,
+        data_type,
+        parameters: Vec::new(),
+        raw_parameters: Vec::new(),
+        raw: None,
+        http_version: None,
+    }))
+}
+
+/// supports bearer, basic and apikey auth types
+fn add_auth(
+    auth: &Value,
+    headers: &mut Vec<(String, String)>,
+    url: &mut Url,
+    resolve: &dyn Fn(&str) -> String,
+) {
+    let kind = auth.get("type").and_then(|x| x.as_str()).unwrap_or("noauth");
+
+    // auth parameters are stored as [{"key": "token", "value": ".."}]
+    let params: HashMap<String, String> = key_values_ordered(auth.get(kind))
+        .into_iter()
+        .map(|(k, v)| (k, resolve(&v)))
+        .collect();
+    let param = |name: &str| params.get(name).cloned().unwrap_or_default();
+
+    match kind {
+        "bearer" => headers.push((
+            "Authorization".to_string(),
+            format!("Bearer {}", param("token")),
+        )),
+        "basic" => headers.push((
+            "Authorization".to_string(),
+            format!(
+                "Basic {}",
+                STANDARD.encode(format!("{}:{}", param("username"), param("password")))
+            ),
+        )),
+        "apikey" => {
+            if param("in") == "query" {
+                url.query_pairs_mut().append_pair(&param("key"), &param("value"));
+            } else {
+                headers.push((param("key"), param("value")));
+            }
+        }
+        _ => (),
+    }
+}
+
+/// replaces {{variable}} with its value. Unknown variables (like {{random}}) are kept
+fn resolve_variables(s: &str, variables: &HashMap<String, String>) -> String {
+    lazy_static! {
+        static ref RE_VARIABLE: Regex = Regex::new(r"\{\{([^{}]+)\}\}").unwrap();
+    }
+
+    let mut s = s.to_string();
+
+    for _ in 0..MAX_VARIABLE_DEPTH {
+        if !RE_VARIABLE.is_match(&s) {
+            break;
+        }
+
+        let replaced = RE_VARIABLE
+            .replace_all(&s, |caps: &Captures| match variables.get(caps[1].trim()) {
+                Some(val) => val.to_owned(),
+                None => caps[0].to_string(),
+            })
+            .to_string();
+
+        if replaced == s {
+            break;
+        }
+
+        s = replaced;
+    }
+
+    s
+}
+
+/// returns enabled key-value pairs from [{"key": .., "value": .., "disabled": false}] arrays
+fn key_values_ordered(value: Option<&Value>) -> Vec<(String, String)> {
+    value
+        .and_then(|x| x.as_array())
+        .into_iter()
+        .flatten()
+        .filter(|x| x.get("disabled").and_then(|x| x.as_bool()) != Some(true))
+        // environments use "enabled" instead
+        .filter(|x| x.get("enabled").and_then(|x| x.as_bool()) != Some(false))
+        // files within formdata
+        .filter(|x| x.get("type").and_then(|x| x.as_str()) != Some("file"))
+        .filter_map(|x| {
+            let key = x.get("key").and_then(|x| x.as_str())?;
+            let value = match x.get("value") {
+                Some(Value::String(val)) => val.to_owned(),
+                Some(Value::Null) | None => String::new(),
+                Some(val) => val.to_string(),
+            };
+            Some((key.to_string(), value))
+        })
+        .collect()
+}
+
+fn key_values(value: Option<&Value>) -> HashMap<String, String> {
+    key_values_ordered(value).into_iter().collect()
+}
@@ -1,6 +1,27 @@
-use std::{collections::HashMap, time::Duration};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
-use crate::network::utils::DataType;
+use regex::Regex;
+
+use crate::network::{rate::RateLimiter, retry::RetryPolicy, utils::DataType};
+
+use super::{mutations::Mutations, wordlist::WordlistFile};
+
+/// parameters checked with every value from DEFAULT_CUSTOM_VALUES like admin=true
+pub const DEFAULT_CUSTOM_PARAMETERS: [&str; 11] = [
+    "admin",
+    "bot",
+    "captcha",
+    "debug",
+    "disable",
+    "encryption",
+    "env",
+    "show",
+    "sso",
+    "test",
+    "waf",
+];
+
+pub const DEFAULT_CUSTOM_VALUES: [&str; 8] = ["1", "0", "false", "off", "null", "true", "yes", "no"];
 
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -10,14 +31,27 @@ pub struct Config {
     /// a list of methods to check urls with
     pub methods: Vec<String>,
 
+    /// url:method pairs to check. Every pair has its own headers and body
+    /// either copied from the default ones or imported from a file
+    pub targets: Vec<Target>,
+
     /// custom user supplied headers or default ones
     pub custom_headers: Vec<(String, String)>,
 
     /// how much to sleep between requests in millisecs
     pub delay: Duration,
 
-    /// user supplied wordlist file
-    pub wordlist: String,
+    /// --rate and --host-rate limits shared by every runner
+    pub rate_limiter: Arc<RateLimiter>,
+
+    /// how many times and on what errors to retry failed requests
+    pub retry_policy: RetryPolicy,
+
+    /// body patterns that mean the server limits the rate of requests (in addition to 429 and 503 with Retry-After)
+    pub rate_limit_signatures: Vec<Regex>,
+
+    /// user supplied wordlist files. Can be tagged with an injection place
+    pub wordlists: Vec<WordlistFile>,
 
     /// max amount of parameters to send per request.
     /// Can be specified by user otherwise detects automatically based on the request method
@@ -137,4 +171,133 @@ pub struct Config {
 
     /// check body of responses with binary content type
     pub check_binary: bool,
+
+    /// file to save the progress of the scan to
+    pub state_file: String,
+
+    /// whether to continue the scan from the state file
+    pub resume: bool,
+
+    /// the json output of the previous scan to compare found parameters with
+    pub baseline: String,
+
+    /// report only parameters that weren't found within the baseline
+    pub only_new: bool,
+
+    /// the sqlite database to record the scan to
+    pub db: String,
+
+    /// fetch same-origin scripts and their source maps to extract parameters from them
+    pub mine_scripts: bool,
+
+    /// variants to generate from the wordlist entries
+    pub mutations: Mutations,
+}
+
+/// the same defaults as the command line arguments have
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            urls: Vec::new(),
+            methods: vec!["GET".to_string()],
+            targets: Vec::new(),
+            custom_headers: Vec::new(),
+            delay: Duration::from_millis(0),
+            rate_limiter: Arc::new(RateLimiter::default()),
+            retry_policy: RetryPolicy::default(),
+            rate_limit_signatures: Vec::new(),
+            wordlists: Vec::new(),
+            max: None,
+            template: None,
+            joiner: None,
+            encode: false,
+            body: String::new(),
+            data_type: None,
+            disable_custom_parameters: false,
+            proxy: String::new(),
+            output_file: String::new(),
+            append: false,
+            remove_empty: false,
+            output_format: "standart".to_string(),
+            save_responses: String::new(),
+            force: false,
+            strict: false,
+            custom_parameters: DEFAULT_CUSTOM_PARAMETERS
+                .iter()
+                .map(|x| (x.to_string(), DEFAULT_CUSTOM_VALUES.iter().map(|x| x.to_string()).collect()))
+                .collect(),
+            disable_progress_bar: false,
+            progress_bar_len: 26,
+            replay_proxy: String::new(),
+            replay_once: false,
+            test: false,
+            verbose: 1,
+            learn_requests_count: 9,
+            recursion_depth: 0,
+            concurrency: 1,
+            workers: 1,
+            timeout: 15,
+            verify: false,
+            reflected_only: false,
+            one_worker_per_host: false,
+            http_version: None,
+            invert: false,
+            headers_discovery: false,
+            follow_redirects: false,
+            disable_colors: false,
+            remove_banner: false,
+            disable_trustdns: false,
+            check_binary: false,
+            state_file: String::new(),
+            resume: false,
+            baseline: String::new(),
+            only_new: false,
+            db: String::new(),
+            mine_scripts: false,
+            mutations: Mutations::default(),
+        }
+    }
+}
+
+/// request data of a single url:method pair
+#[derive(Debug, Clone)]
+pub struct Target {
+    pub method: String,
+
+    /// url with an optional injection point
+    pub url: String,
+
+    pub headers: Vec<(String, String)>,
+
+    pub body: String,
+
+    pub data_type: Option<DataType>,
+
+    /// documented parameters with example values to add to every request
+    /// they are excluded from the wordlist
+    pub parameters: Vec<(String, String)>,
+
+    /// documented parameters with json objects or arrays as values
+    pub raw_parameters: Vec<String>,
+
+    /// whether to send parameters within the other place than the method's default one (like --invert)
+    pub invert: bool,
+
+    /// the request file to send exactly as it is (--verbatim)
+    pub raw: Option<String>,
+
+    /// http version of the imported request. --http has a priority against it
+    pub http_version: Option<http::Version>,
+}
+
+impl Target {
+    /// imported requests are checked once per method, url, headers and body.
+    /// Requests with the same method and url but a different body are different targets
+    pub fn is_same_request(&self, other: &Target) -> bool {
+        self.method == other.method
+            && self.url == other.url
+            && self.headers == other.headers
+            && self.body == other.body
+            && self.raw == other.raw
+    }
 }
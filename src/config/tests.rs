@@ -0,0 +1,535 @@
+#[cfg(test)]
+mod tests {
+    use regex::Regex;
+
+    use crate::{
+        config::{
+            args::{self, arg_names, config_app}, burp::parse_burp_xml, curl::parse_curl,
+            file::{parse_config, Args}, har::parse_har,
+            mutations::Mutations, openapi::parse_openapi,
+            postman::parse_postman,
+            utils::{read_request_files, request_filenames, RequestFilter},
+            wordlist::{builtin, WordlistFile, BUILTIN_WORDLISTS},
+        },
+        network::utils::DataType,
+    };
+
+    const CONFIG: &str = r#"
+concurrency = 2
+headers = ["X-Team: appsec", "Accept: */*"]
+
+[profile.stealth]
+concurrency = 1
+delay = 500
+disable-custom-parameters = true
+"#;
+
+    #[test]
+    fn config_file_top_level() {
+        let values = parse_config(CONFIG, None, &config_app()).unwrap();
+
+        assert_eq!(values["concurrency"], vec!["2"]);
+        assert_eq!(values["headers"], vec!["X-Team: appsec", "Accept: */*"]);
+        assert!(!values.contains_key("delay"));
+    }
+
+    #[test]
+    fn config_file_profile() {
+        let values = parse_config(CONFIG, Some("stealth"), &config_app()).unwrap();
+
+        assert_eq!(values["concurrency"], vec!["1"]);
+        assert_eq!(values["delay"], vec!["500"]);
+        assert_eq!(values["disable-custom-parameters"], vec!["true"]);
+        assert_eq!(values["headers"].len(), 2);
+
+        assert!(parse_config(CONFIG, Some("missing"), &config_app()).is_err());
+    }
+
+    #[test]
+    fn config_file_unknown_keys() {
+        assert!(parse_config("concurency = 2", None, &config_app()).is_err());
+        assert!(parse_config("[profile.fast]\ndelya = 0", Some("fast"), &config_app()).is_err());
+        // profiles that aren't chosen are checked as well
+        assert!(parse_config("[profile.fast]\ndelya = 0", None, &config_app()).is_err());
+    }
+
+    #[test]
+    fn config_file_values() {
+        // the same validation as for the command line arguments
+        assert!(parse_config("mutate = [\"camel\", \"typo\"]", None, &config_app()).is_err());
+        assert!(parse_config("retry-on = \"timeout\"", None, &config_app()).is_ok());
+        assert!(parse_config("[profile.a]\nretry-on = \"never\"", None, &config_app()).is_err());
+        assert!(parse_config("verify = \"yes\"", None, &config_app()).is_err());
+        // requirements can be satisfied via the command line
+        assert!(parse_config("proto = \"http\"", None, &config_app()).is_ok());
+    }
+
+    #[test]
+    fn config_file_overrides() {
+        let app = config_app();
+        let file = parse_config("url = \"https://example.com/\"\ndelay = 100", None, &app).unwrap();
+
+        // --har conflicts with --url, so the url from the file is ignored
+        let matches = args::app().get_matches_from(vec!["x8", "--har", "requests.har"]);
+        let args = Args::new(matches, file.clone(), &app, &arg_names());
+        assert!(args.value_of("url").is_none());
+        assert_eq!(args.value_of("delay"), Some("100"));
+
+        let matches = args::app().get_matches_from(vec!["x8", "-w", "words.txt"]);
+        let args = Args::new(matches, file, &app, &arg_names());
+        assert_eq!(args.value_of("url"), Some("https://example.com/"));
+    }
+
+    const HAR: &str = r#"{"log": {"entries": [
+        {
+            "request": {
+                "method": "POST",
+                "url": "https://api.example.com/v1/users",
+                "httpVersion": "HTTP/2",
+                "headers": [
+                    {"name": ":authority", "value": "api.example.com"},
+                    {"name": "content-type", "value": "application/json"},
+                    {"name": "content-length", "value": "13"}
+                ],
+                "postData": {"mimeType": "application/json", "text": "{\"name\":\"a\"}"}
+            },
+            "response": {"content": {"mimeType": "application/json"}}
+        },
+        {
+            "request": {"method": "GET", "url": "https://example.com/static/app.js", "headers": []},
+            "response": {"content": {"mimeType": "application/javascript"}}
+        },
+        {
+            "request": {"method": "GET", "url": "https://example.com/search?q=1", "headers": []},
+            "response": {"content": {"mimeType": "text/html"}}
+        },
+        {
+            "request": {"method": "GET", "url": "https://example.com/search?q=1", "headers": []},
+            "response": {"content": {"mimeType": "text/html"}}
+        },
+        {
+            "request": {
+                "method": "POST",
+                "url": "https://api.example.com/v1/users",
+                "headers": [{"name": "content-type", "value": "application/json"}],
+                "postData": {"mimeType": "application/json", "text": "{\"name\":\"b\"}"}
+            },
+            "response": {"content": {"mimeType": "application/json"}}
+        }
+    ]}}"#;
+
+    #[test]
+    fn har_entries() {
+        let targets = parse_har(HAR, &RequestFilter::default()).unwrap();
+
+        // the repeated search request is checked once, while the same endpoint with another body is a separate target
+        assert_eq!(targets.len(), 4);
+        assert_eq!(targets[0].method, "POST");
+        assert_eq!(targets[0].body, "{\"name\":\"a\"}");
+        assert_eq!(targets[0].data_type, Some(DataType::ProbablyJson));
+        assert_eq!(targets[0].http_version, Some(http::Version::HTTP_2));
+        assert_eq!(
+            targets[0].headers,
+            vec![("content-type".to_string(), "application/json".to_string())]
+        );
+        assert_eq!(targets[2].url, "https://example.com/search?q=1");
+        assert_eq!(targets[2].http_version, None);
+        assert_eq!(targets[3].body, "{\"name\":\"b\"}");
+    }
+
+    #[test]
+    fn har_filters() {
+        let filter = RequestFilter {
+            exclude_mime: vec!["javascript".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(parse_har(HAR, &filter).unwrap().len(), 3);
+
+        let filter = RequestFilter {
+            include_hosts: vec!["example.com".to_string()],
+            include_path: Some(Regex::new("^/v1/").unwrap()),
+            ..Default::default()
+        };
+        let targets = parse_har(HAR, &filter).unwrap();
+        assert_eq!(targets.len(), 2);
+        assert_eq!(targets[0].url, "https://api.example.com/v1/users");
+    }
+
+    const BURP_XML: &str = r#"<?xml version="1.0"?>
+<!DOCTYPE items [
+<!ELEMENT items (item*)>
+<!ATTLIST items burpVersion CDATA "">
+<!ATTLIST items exportTime CDATA "">
+]>
+<items burpVersion="2023.1" exportTime="Mon Jan 01 00:00:00 UTC 2024">
+  <item>
+    <url><![CDATA[http://example.com:8080/api/login]]></url>
+    <host ip="127.0.0.1">example.com</host>
+    <port>8080</port>
+    <protocol>http</protocol>
+    <method><![CDATA[POST]]></method>
+    <request base64="true"><![CDATA[UE9TVCAvYXBpL2xvZ2luIEhUVFAvMS4xDQpIb3N0OiBpbnRlcm5hbC5sb2NhbA0KQ29udGVudC1UeXBlOiBhcHBsaWNhdGlvbi9qc29uDQpDb250ZW50LUxlbmd0aDogMTUNCg0KeyJ1c2VyIjoidGVzdCJ9]]></request>
+    <mimetype>JSON</mimetype>
+  </item>
+  <item>
+    <host ip="127.0.0.1">example.com</host>
+    <port>443</port>
+    <protocol>https</protocol>
+    <request base64="false"><![CDATA[GET /search?q=1 HTTP/2
+Host: example.com
+
+]]></request>
+    <mimetype>HTML</mimetype>
+  </item>
+  <item>
+    <host ip="127.0.0.1">example.com</host>
+    <port>443</port>
+    <protocol>https</protocol>
+    <request base64="false"><![CDATA[GET /search?q=1 HTTP/2
+Host: example.com
+
+]]></request>
+    <mimetype>HTML</mimetype>
+  </item>
+  <item>
+    <host ip="127.0.0.1">example.com</host>
+    <port>443</port>
+    <protocol>https</protocol>
+    <request base64="false"><![CDATA[GET /search?q=1 HTTP/2
+Host: example.com
+X-Debug: 1
+
+]]></request>
+    <mimetype>HTML</mimetype>
+  </item>
+</items>"#;
+
+    #[test]
+    fn burp_xml_items() {
+        let targets = parse_burp_xml(BURP_XML, &RequestFilter::default()).unwrap();
+
+        // the repeated request is checked once, while the one with another header is a separate target
+        assert_eq!(targets.len(), 3);
+        assert_eq!(targets[0].method, "POST");
+        assert_eq!(targets[0].url, "http://example.com:8080/api/login");
+        assert_eq!(targets[0].body, "{\"user\":\"test\"}");
+        assert_eq!(targets[0].data_type, Some(DataType::ProbablyJson));
+        assert_eq!(targets[0].http_version, Some(http::Version::HTTP_11));
+        assert_eq!(targets[1].url, "https://example.com/search?q=1");
+        assert_eq!(targets[1].http_version, Some(http::Version::HTTP_2));
+        assert!(targets[2].headers.contains(&("X-Debug".to_string(), "1".to_string())));
+
+        let filter = RequestFilter {
+            include_mime: vec!["html".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(parse_burp_xml(BURP_XML, &filter).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn request_files() {
+        let dir = std::env::temp_dir().join(format!("x8-requests-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // differ only in the body
+        std::fs::write(dir.join("a.req"), "POST /login HTTP/1.1\nHost: example.com\n\nuser=a").unwrap();
+        std::fs::write(dir.join("b.req"), "POST /login HTTP/1.1\nHost: example.com\n\nuser=b").unwrap();
+        std::fs::write(dir.join("c.txt"), "GET /search HTTP/2\nHost: example.com:8443\n\n").unwrap();
+        // the same request as a.req
+        std::fs::write(dir.join("d.req"), "POST /login HTTP/1.1\nHost: example.com\n\nuser=a").unwrap();
+
+        let filenames = request_filenames(dir.to_str().unwrap()).unwrap();
+        assert_eq!(filenames.len(), 4);
+
+        let pattern = dir.join("*.req");
+        assert_eq!(request_filenames(pattern.to_str().unwrap()).unwrap().len(), 3);
+        assert!(request_filenames(dir.join("*.json").to_str().unwrap()).is_err());
+
+        let targets = read_request_files(
+            &filenames, "https", None, None, None, &RequestFilter::default(), false, false,
+        )
+        .unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(targets.len(), 3);
+        assert_eq!(targets[0].body, "user=a");
+        assert_eq!(targets[1].body, "user=b");
+        assert_eq!(targets[0].http_version, Some(http::Version::HTTP_11));
+        assert_eq!(targets[2].method, "GET");
+        assert_eq!(targets[2].url, "https://example.com:8443/search");
+        assert_eq!(targets[2].http_version, Some(http::Version::HTTP_2));
+    }
+
+    const OPENAPI: &str = r##"{
+        "openapi": "3.0.0",
+        "servers": [{"url": "https://api.example.com/v1"}],
+        "paths": {
+            "/users/{id}": {
+                "parameters": [{"name": "id", "in": "path", "schema": {"type": "integer", "example": 5}}],
+                "get": {
+                    "parameters": [{"name": "fields", "in": "query", "schema": {"type": "string", "default": "name"}}]
+                },
+                "put": {
+                    "requestBody": {"content": {"application/json": {"schema": {"$ref": "#/components/schemas/User"}}}}
+                }
+            }
+        },
+        "components": {"schemas": {"User": {
+            "type": "object",
+            "properties": {"name": {"type": "string"}, "tags": {"type": "array", "items": {"type": "string"}}}
+        }}}
+    }"##;
+
+    #[test]
+    fn openapi_operations() {
+        let targets = parse_openapi(OPENAPI, None, &[], &RequestFilter::default()).unwrap();
+
+        assert_eq!(targets.len(), 2);
+        assert_eq!(targets[0].method, "GET");
+        assert_eq!(targets[0].url, "https://api.example.com/v1/users/5");
+        assert_eq!(targets[0].parameters, vec![("fields".to_string(), "name".to_string())]);
+        assert_eq!(targets[0].data_type, None);
+
+        assert_eq!(targets[1].method, "PUT");
+        assert_eq!(targets[1].data_type, Some(DataType::Json));
+        assert!(!targets[1].invert);
+        assert_eq!(
+            targets[1].parameters,
+            vec![
+                ("name".to_string(), "test".to_string()),
+                ("tags".to_string(), "[\"test\"]".to_string())
+            ]
+        );
+        assert_eq!(targets[1].raw_parameters, vec!["tags".to_string()]);
+
+        let targets = parse_openapi(OPENAPI, Some("http://localhost/"), &[], &RequestFilter::default()).unwrap();
+        assert_eq!(targets[0].url, "http://localhost/users/5");
+    }
+
+    #[test]
+    fn openapi_recursive_schema() {
+        let spec = r##"{
+            "openapi": "3.0.0",
+            "servers": [{"url": "https://api.example.com"}],
+            "paths": {"/tree": {"post": {"requestBody": {"content": {"application/json": {
+                "schema": {"type": "object", "properties": {"node": {"$ref": "#/components/schemas/Node"}}}
+            }}}}}},
+            "components": {"schemas": {"Node": {"type": "array", "items": {"$ref": "#/components/schemas/Node"}}}}
+        }"##;
+
+        let targets = parse_openapi(spec, None, &[], &RequestFilter::default()).unwrap();
+
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].parameters[0].0, "node");
+        assert!(targets[0].parameters[0].1.starts_with("[[["));
+        assert_eq!(targets[0].raw_parameters, vec!["node".to_string()]);
+    }
+
+    const POSTMAN: &str = r#"{
+        "info": {"schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json"},
+        "variable": [{"key": "host", "value": "https://dev.example.com"}, {"key": "id", "value": "1"}],
+        "item": [{
+            "name": "users",
+            "variable": [{"key": "id", "value": "2"}],
+            "auth": {"type": "bearer", "bearer": [{"key": "token", "value": "{{token}}"}]},
+            "item": [
+                {
+                    "name": "update",
+                    "request": {
+                        "method": "PATCH",
+                        "header": [{"key": "X-Debug", "value": "1", "disabled": true}],
+                        "url": {"raw": "{{host}}/users/{{id}}"},
+                        "body": {"mode": "raw", "raw": "{\n  \"name\": \"a\"\n}\n", "options": {"raw": {"language": "json"}}}
+                    }
+                },
+                {
+                    "name": "login",
+                    "request": {
+                        "auth": {"type": "noauth"},
+                        "method": "GET",
+                        "url": "{{host}}/login",
+                        "body": {"mode": "urlencoded", "urlencoded": [{"key": "user", "value": "admin"}, {"key": "next", "value": "/a b&c"}]}
+                    }
+                },
+                {
+                    "name": "login again",
+                    "request": {
+                        "auth": {"type": "noauth"},
+                        "method": "GET",
+                        "url": "{{host}}/login",
+                        "body": {"mode": "urlencoded", "urlencoded": [{"key": "user", "value": "admin"}, {"key": "next", "value": "/a b&c"}]}
+                    }
+                },
+                {
+                    "name": "guest login",
+                    "request": {
+                        "auth": {"type": "noauth"},
+                        "method": "GET",
+                        "url": "{{host}}/login",
+                        "body": {"mode": "urlencoded", "urlencoded": [{"key": "user", "value": "guest"}, {"key": "next", "value": "/a b&c"}]}
+                    }
+                }
+            ]
+        }]
+    }"#;
+
+    const POSTMAN_ENV: &str = r#"{"values": [
+        {"key": "host", "value": "https://api.example.com", "enabled": true},
+        {"key": "token", "value": "secret", "enabled": true}
+    ]}"#;
+
+    #[test]
+    fn postman_requests() {
+        let targets = parse_postman(POSTMAN, Some(POSTMAN_ENV), &RequestFilter::default()).unwrap();
+
+        // the repeated login request is checked once, while the one with another body is a separate target
+        assert_eq!(targets.len(), 3);
+        assert_eq!(targets[0].method, "PATCH");
+        assert_eq!(targets[0].url, "https://api.example.com/users/2");
+        assert_eq!(targets[0].body, "{\n  \"name\": \"a\"\n}");
+        assert_eq!(targets[0].data_type, Some(DataType::Json));
+        assert_eq!(
+            targets[0].headers,
+            vec![
+                ("Authorization".to_string(), "Bearer secret".to_string()),
+                ("Content-Type".to_string(), "application/json".to_string())
+            ]
+        );
+        assert!(!targets[0].invert);
+
+        // a get request with a body should be checked within the body
+        assert_eq!(targets[1].body, "user=admin&next=%2Fa+b%26c");
+        assert_eq!(targets[1].data_type, Some(DataType::Urlencoded));
+        assert!(targets[1].invert);
+        assert!(!targets[1].headers.iter().any(|x| x.0 == "Authorization"));
+        assert_eq!(targets[2].body, "user=guest&next=%2Fa+b%26c");
+
+        let targets = parse_postman(POSTMAN, None, &RequestFilter::default()).unwrap();
+        assert_eq!(targets[0].url, "https://dev.example.com/users/2");
+    }
+
+    #[test]
+    fn curl_command() {
+        let command = r#"curl 'https://example.com/api/v1/users?id=1' \
+  -H 'accept-encoding: gzip, deflate, br' \
+  -H 'content-type: application/json' \
+  -b 'session=abc; theme=dark' \
+  --data-raw $'{"name":"it\'s"}' \
+  --compressed"#;
+
+        let (methods, urls, headers, body, data_type, _) = parse_curl(command, None).unwrap();
+
+        assert_eq!(methods, vec!["POST"]);
+        assert_eq!(urls, vec!["https://example.com/api/v1/users?id=1"]);
+        assert_eq!(body, "{\"name\":\"it's\"}");
+        assert_eq!(data_type, Some(DataType::ProbablyJson));
+        assert_eq!(
+            headers,
+            vec![
+                ("content-type".to_string(), "application/json".to_string()),
+                ("Cookie".to_string(), "session=abc; theme=dark".to_string())
+            ]
+        );
+
+        let (methods, urls, headers, body, ..) =
+            parse_curl("curl -XPUT example.com/login --data \"user=admin\"", None).unwrap();
+        assert_eq!(methods, vec!["PUT"]);
+        assert_eq!(urls, vec!["http://example.com/login"]);
+        assert_eq!(body, "user=admin");
+        assert_eq!(
+            headers.last().unwrap(),
+            &("Content-Type".to_string(), "application/x-www-form-urlencoded".to_string())
+        );
+
+        let filename = std::env::temp_dir()
+            .join(format!("x8-curl-data-{}", std::process::id()))
+            .to_string_lossy()
+            .to_string();
+        std::fs::write(&filename, "id=1\n").unwrap();
+
+        let (_, urls, _, body, ..) = parse_curl(
+            &format!("curl -G example.com -d @{} --data-urlencode 'q=a b&c' --data-urlencode =x/y", filename),
+            None,
+        )
+        .unwrap();
+        std::fs::remove_file(&filename).unwrap();
+        assert_eq!(urls, vec!["http://example.com/?id=1&q=a%20b%26c&x%2Fy"]);
+        assert_eq!(body, "");
+
+        let (methods, ..) = parse_curl("curl -I https://example.com", None).unwrap();
+        assert_eq!(methods, vec!["HEAD"]);
+
+        // the values of known options aren't taken as the url, while unknown options are errors
+        let (_, urls, ..) = parse_curl("curl -sk --ciphers ECDHE https://example.com/ --resolve example.com:443:127.0.0.1", None).unwrap();
+        assert_eq!(urls, vec!["https://example.com/"]);
+        assert!(parse_curl("curl --unknown value https://example.com/", None).is_err());
+    }
+
+    #[test]
+    fn wordlist_mutations() {
+        let words = vec!["user_name".to_string(), "category".to_string(), "userName".to_string()];
+
+        let mutations = Mutations {
+            camel: true,
+            kebab: true,
+            plural: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            mutations.apply(words.clone()),
+            vec![
+                "user_name", "category", "userName", "user-name", "user_names", "userNames",
+                "user-names", "categories"
+            ]
+        );
+
+        let mutations = Mutations {
+            prefixes: vec!["_".to_string(), "is".to_string()],
+            suffixes: vec!["Id".to_string(), "[]".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            mutations.apply(vec!["admin".to_string(), "user_name".to_string()]),
+            vec![
+                "admin", "user_name", "_admin", "isAdmin", "adminId", "admin[]", "_user_name",
+                "is_user_name", "user_name_id", "user_name[]"
+            ]
+        );
+
+        assert_eq!(Mutations::default().apply(words.clone()), words);
+    }
+
+    #[test]
+    fn wordlist_places() {
+        assert_eq!(
+            WordlistFile::parse("headers:headers.txt"),
+            WordlistFile {
+                place: Some("headers".to_string()),
+                filename: "headers.txt".to_string()
+            }
+        );
+
+        // windows paths and unknown prefixes aren't places
+        for value in ["params.txt", "C:\\params.txt", "dir:params.txt"] {
+            assert_eq!(
+                WordlistFile::parse(value),
+                WordlistFile {
+                    place: None,
+                    filename: value.to_string()
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn builtin_wordlists() {
+        for (name, _, _) in BUILTIN_WORDLISTS.iter() {
+            let words = builtin(name).unwrap();
+            assert!(!words.is_empty());
+            assert!(words.iter().all(|x| !x.is_empty() && x.trim() == x));
+        }
+
+        assert!(builtin("headers").unwrap().contains(&"X-Forwarded-For".to_string()));
+        assert!(builtin("unknown").is_err());
+    }
+}
@@ -1,43 +1,45 @@
 use std::{
-    fs::File,
+    fs::{self, File},
     collections::HashMap,
     error::Error,
     io::{self, BufRead, Write},
+    path::{Path, PathBuf},
 };
 
 use colored::Colorize;
+use regex::Regex;
+use url::Url;
 
-use crate::network::utils::DataType;
+use crate::network::utils::{is_body_method, DataType, Headers};
 
-use super::structs::Config;
+use super::{
+    structs::{Config, Target},
+    wordlist::Wordlists,
+};
 
 /// shorcut to convert Option<&str> to Option<String> to be able to return it from the function
 pub(super) fn convert_to_string_if_some(el: Option<&str>) -> Option<String> {
-    if let Some(val) = el {
-        Some(val.to_string())
-    } else {
-        None
-    }
+    el.map(|val| val.to_string())
 }
 
+/// the parts of a request from the request file or a curl command
+pub(super) type ParsedRequest = (
+    Vec<String>,              // method
+    Vec<String>,              // url
+    Vec<(String, String)>,    // headers
+    String,                   // body
+    Option<DataType>,
+    Option<reqwest::Version>, // http version
+);
+
 /// parse request from the request file
-pub(super) fn parse_request<'a>(
-    request: &'a str,
+pub(super) fn parse_request(
+    request: &str,
     scheme: &str,
     port: Option<u16>,
     mut data_type: Option<DataType>,
     split_by: Option<&str>,
-) -> Result<
-    (
-        Vec<String>,              // method
-        Vec<String>,              // url
-        Vec<(String, String)>,    // headers
-        String,                   // body
-        Option<DataType>,
-        Option<reqwest::Version>, // http version
-    ),
-    Box<dyn Error>,
-> {
+) -> Result<ParsedRequest, Box<dyn Error>> {
     // request by lines
     let lines = if let Some(val) = split_by {
         request
@@ -81,10 +83,8 @@ pub(super) fn parse_request<'a>(
         .concat();
 
         match key.to_lowercase().as_str() {
-            "content-type" => {
-                if value.contains("json") && data_type.is_none() {
-                    data_type = Some(DataType::ProbablyJson)
-                }
+            "content-type" if value.contains("json") && data_type.is_none() => {
+                data_type = Some(DataType::ProbablyJson)
             }
             "host" => {
                 host = value.clone();
@@ -111,9 +111,9 @@ pub(super) fn parse_request<'a>(
     }
 
     // port from the --port argument has a priority against port within the host header
-    let (host, port) = if port.is_some() {
-       (host.split(':').next().unwrap().to_string(), port.unwrap())
-    } else if port.is_none() && host.contains(':') {
+    let (host, port) = if let Some(port) = port {
+       (host.split(':').next().unwrap().to_string(), port)
+    } else if host.contains(':') {
         let mut host = host.split(':');
         (host.next().unwrap().to_string(), host.next().unwrap().parse()?)
     } else {
@@ -135,7 +135,61 @@ pub(super) fn parse_request<'a>(
     ))
 }
 
-pub fn write_banner_config(config: &Config, params: &Vec<String>) {
+/// filters for requests imported from files like har
+#[derive(Debug, Default)]
+pub struct RequestFilter {
+    pub include_hosts: Vec<String>,
+    pub exclude_hosts: Vec<String>,
+
+    /// matched against the path with query
+    pub include_path: Option<Regex>,
+    pub exclude_path: Option<Regex>,
+
+    /// lowercased parts of the response's mime type
+    pub include_mime: Vec<String>,
+    pub exclude_mime: Vec<String>,
+}
+
+impl RequestFilter {
+    pub fn matches(&self, url: &Url, mime_type: Option<&str>) -> bool {
+        // example.com matches example.com and sub.example.com
+        fn host_matches(host: &str, hosts: &[String]) -> bool {
+            hosts
+                .iter()
+                .any(|x| host == x || host.ends_with(&format!(".{}", x)))
+        }
+
+        let host = url.host_str().unwrap_or("");
+        let path = &url[url::Position::BeforePath..];
+
+        // mime type filters are skipped for formats without responses
+        let mime_type_matches = match mime_type {
+            Some(mime_type) => {
+                let mime_type = mime_type.to_lowercase();
+                (self.include_mime.is_empty() || self.include_mime.iter().any(|x| mime_type.contains(x)))
+                    && !self.exclude_mime.iter().any(|x| mime_type.contains(x))
+            }
+            None => true,
+        };
+
+        (self.include_hosts.is_empty() || host_matches(host, &self.include_hosts))
+            && !host_matches(host, &self.exclude_hosts)
+            && self.include_path.as_ref().is_none_or(|x| x.is_match(path))
+            && !self.exclude_path.as_ref().is_some_and(|x| x.is_match(path))
+            && mime_type_matches
+    }
+}
+
+/// --cookies option. Adds the injection point to the end of the cookie header
+pub(super) fn add_cookies_injection_point(headers: &mut Vec<(String, String)>) {
+    if let Some(index) = headers.get_index_case_insensitive("cookie") {
+        headers[index] = (headers[index].0.clone(), headers[index].1.clone()+";%s")
+    } else {
+        headers.push(("Cookie".to_string(), "%s".to_string()));
+    }
+}
+
+pub fn write_banner_config(config: &Config, wordlists: &Wordlists) {
     let mut output = format!(
         "{}:         {}\n{}:      {}\n{}: {}",
         "urls".green(),
@@ -143,9 +197,14 @@ pub fn write_banner_config(config: &Config, params: &Vec<String>) {
         "methods".blue(),
         config.methods.join(" "),
         "wordlist len".cyan(),
-        params.len(),
+        wordlists.len(),
     );
 
+    // tagged wordlists replace the default one for their injection place
+    for (place, params) in wordlists.places.iter() {
+        output += &format!("\n{}:{} {}", place.cyan(), " ".repeat(12 - place.len()), params.len())
+    }
+
     if !config.proxy.is_empty() {
         output += &format!("\n{}:        {}", "proxy".green(), &config.proxy)
     }
@@ -180,7 +239,149 @@ pub fn read_urls_if_possible(filename: &str) -> Result<Option<Vec<String>>, io::
     Ok(Some(urls))
 }
 
-pub(super) fn add_default_headers(curr_headers: HashMap<&str, String>) -> Vec<(String, String)> {
+/// returns files from the directory or matching the glob pattern.
+/// Otherwise returns the filename itself
+pub(super) fn request_filenames(path: &str) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut filenames = if Path::new(path).is_dir() {
+        fs::read_dir(path)?
+            .filter_map(|x| x.ok())
+            .map(|x| x.path())
+            .filter(|x| x.is_file())
+            .collect::<Vec<PathBuf>>()
+    } else if path.contains(['*', '?', '[']) {
+        glob::glob(path)?
+            .filter_map(|x| x.ok())
+            .filter(|x| x.is_file())
+            .collect::<Vec<PathBuf>>()
+    } else {
+        return Ok(vec![PathBuf::from(path)]);
+    };
+
+    if filenames.is_empty() {
+        Err(format!("No request files found in {}", path))?;
+    }
+
+    // to have the same order of targets between runs
+    filenames.sort();
+
+    Ok(filenames)
+}
+
+/// parses every request file into a separate target
+#[allow(clippy::too_many_arguments)]
+pub(super) fn read_request_files(
+    filenames: &[PathBuf],
+    scheme: &str,
+    port: Option<u16>,
+    data_type: Option<DataType>,
+    split_by: Option<&str>,
+    filter: &RequestFilter,
+    verbatim: bool,
+    headers_discovery: bool,
+) -> Result<Vec<Target>, Box<dyn Error>> {
+    let mut targets: Vec<Target> = Vec::new();
+
+    for filename in filenames {
+        let request = fs::read_to_string(filename)?;
+
+        let parsed = if verbatim {
+            parse_request(&without_injection_lines(&request), scheme, port, data_type.clone(), split_by)
+        } else {
+            parse_request(&request, scheme, port, data_type.clone(), split_by)
+        };
+
+        let (methods, urls, headers, body, data_type, http_version) =
+            match parsed {
+                Ok(val) => val,
+                Err(err) => Err(format!("Unable to parse {}: {}", filename.display(), err))?,
+            };
+
+        if !filter.matches(&Url::parse(&urls[0])?, None) {
+            continue;
+        }
+
+        let mut target = Target {
+            method: methods[0].to_owned(),
+            url: urls[0].to_owned(),
+            headers,
+            body,
+            data_type,
+            parameters: Vec::new(),
+            raw_parameters: Vec::new(),
+            invert: false,
+            raw: None,
+            http_version,
+        };
+
+        if verbatim {
+            if let Err(err) = set_verbatim(&mut target, &request, headers_discovery) {
+                Err(format!("{}: {}", filename.display(), err))?;
+            }
+        }
+
+        if !targets.iter().any(|x| x.is_same_request(&target)) {
+            targets.push(target);
+        }
+    }
+
+    if targets.is_empty() {
+        Err("No requests left after filtering")?;
+    }
+
+    Ok(targets)
+}
+
+/// removes lines with the %s injection point only (they're used to add headers in the verbatim mode)
+/// so the request can be parsed
+pub(super) fn without_injection_lines(request: &str) -> String {
+    request
+        .split('\n')
+        .filter(|x| x.trim_end_matches('\r') != "%s")
+        .collect::<Vec<&str>>()
+        .join("\n")
+}
+
+/// makes the target send the request file as it is.
+/// Parameters are sent within the place of the %s injection point
+pub(super) fn set_verbatim(
+    target: &mut Target,
+    request: &str,
+    headers_discovery: bool,
+) -> Result<(), Box<dyn Error>> {
+    let (head, body) = match request
+        .split_once("\r\n\r\n")
+        .or_else(|| request.split_once("\n\n"))
+    {
+        Some(val) => val,
+        None => (request, ""),
+    };
+    let (first_line, headers) = head.split_once('\n').unwrap_or((head, ""));
+
+    // header names are lowercased within http/2 so they can't be preserved anyway
+    if first_line.contains("HTTP/2") {
+        Err("The verbatim mode supports only HTTP/1.x requests")?;
+    }
+
+    let within_body = if first_line.contains("%s") {
+        false
+    } else if headers.contains("%s") {
+        if !headers_discovery {
+            Err("Use --headers to discover headers or header values in the verbatim mode")?;
+        }
+        false
+    } else if body.contains("%s") {
+        true
+    } else {
+        Err("The request file should contain the %s injection point in the verbatim mode")?
+    };
+
+    target.invert = within_body != is_body_method(&target.method);
+    target.raw = Some(request.to_string());
+
+    Ok(())
+}
+
+pub(crate) fn add_default_headers(curr_headers: HashMap<&str, String>) -> Vec<(String, String)> {
     let default_headers = [
         ("User-Agent", "Mozilla/5.0 (Macintosh; Intel Mac OS X 12) AppleWebKit/601.3.9 (KHTML, like Gecko) Version/9.0.2 Firefox/99.0"),
         ("Accept", "*/*"),
@@ -0,0 +1,202 @@
+use std::{
+    collections::HashSet,
+    error::Error,
+    io::{self, Write},
+};
+
+use atty::Stream;
+use linked_hash_map::LinkedHashMap;
+
+use crate::{
+    network::{request::RequestDefaults, utils::InjectionPlace},
+    utils::{read_lines, read_stdin_lines},
+};
+
+use super::structs::Config;
+
+/// injection places wordlists can be tagged with. Example: -w headers:headers.txt
+pub const WORDLIST_PLACES: [&str; 5] = ["query", "body", "json", "headers", "header-value"];
+
+/// wordlists compiled into the binary: (name, description, content).
+/// Can be used via -w builtin:name
+pub const BUILTIN_WORDLISTS: [(&str, &str, &str); 3] = [
+    ("params", "query and body parameters", include_str!("wordlists/params.txt")),
+    ("json", "json keys", include_str!("wordlists/json.txt")),
+    ("headers", "http headers", include_str!("wordlists/headers.txt")),
+];
+
+/// a wordlist provided via -w
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordlistFile {
+    /// None in case the wordlist is used for every injection place
+    pub place: Option<String>,
+    pub filename: String,
+}
+
+impl WordlistFile {
+    /// place:filename or filename
+    pub fn parse(value: &str) -> Self {
+        match value.split_once(':') {
+            Some((place, filename)) if WORDLIST_PLACES.contains(&place) => Self {
+                place: Some(place.to_string()),
+                filename: filename.to_string(),
+            },
+            _ => Self {
+                place: None,
+                filename: value.to_string(),
+            },
+        }
+    }
+
+    pub fn read(&self) -> Result<Vec<String>, Box<dyn Error>> {
+        if let Some(name) = self.filename.strip_prefix("builtin:") {
+            return builtin(name);
+        }
+
+        let mut words = Vec::new();
+        for line in read_lines(&self.filename)?.map_while(Result::ok) {
+            words.push(line);
+        }
+
+        Ok(words)
+    }
+}
+
+/// returns the built-in wordlist by its name
+pub fn builtin(name: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    match BUILTIN_WORDLISTS.iter().find(|x| x.0 == name) {
+        Some((_, _, content)) => Ok(content.lines().map(|x| x.to_string()).collect()),
+        None => Err(format!(
+            "Unknown built-in wordlist: {}. Available: {}",
+            name,
+            BUILTIN_WORDLISTS.map(|x| x.0).join(", ")
+        ))?,
+    }
+}
+
+/// prints the names, sizes and descriptions of the built-in wordlists (--list-wordlists)
+pub fn write_builtin_wordlists() {
+    for (name, description, content) in BUILTIN_WORDLISTS.iter() {
+        writeln!(
+            io::stdout(),
+            "builtin:{:<10}{:>5}  {}",
+            name,
+            content.lines().count(),
+            description
+        )
+        .ok();
+    }
+}
+
+/// parameters from all the provided wordlists
+#[derive(Debug, Clone, Default)]
+pub struct Wordlists {
+    /// merged untagged wordlists (or stdin)
+    pub default: Vec<String>,
+
+    /// injection place -> merged wordlists tagged with it
+    pub places: LinkedHashMap<String, Vec<String>>,
+}
+
+impl Wordlists {
+    /// reads every wordlist (stdin or the built-in ones in case there are no wordlists),
+    /// removes duplicates and applies mutations
+    pub fn read(config: &Config) -> Result<Self, Box<dyn Error>> {
+        let mut wordlists = Self::default();
+
+        for wordlist in config.wordlists.iter() {
+            wordlists.add(wordlist.place.as_deref(), wordlist.read()?);
+        }
+
+        if config.wordlists.is_empty() {
+            // just accept piped stdin
+            if !atty::is(Stream::Stdin) {
+                wordlists.default = read_stdin_lines();
+            } else {
+                wordlists = Self::builtin()?;
+            }
+        }
+
+        Ok(wordlists.prepare(config))
+    }
+
+    /// the built-in params wordlist for every place except for json and headers
+    pub fn builtin() -> Result<Self, Box<dyn Error>> {
+        let mut wordlists = Self {
+            default: builtin("params")?,
+            ..Default::default()
+        };
+        wordlists.places.insert("json".to_string(), builtin("json")?);
+        wordlists.places.insert("headers".to_string(), builtin("headers")?);
+
+        Ok(wordlists)
+    }
+
+    /// appends words to the wordlist of the place or to the default one
+    pub fn add(&mut self, place: Option<&str>, mut words: Vec<String>) {
+        match place {
+            Some(place) => self
+                .places
+                .entry(place.to_owned())
+                .or_default()
+                .append(&mut words),
+            None => self.default.append(&mut words),
+        }
+    }
+
+    /// removes duplicates and applies mutations
+    pub fn prepare(mut self, config: &Config) -> Self {
+        self.default = config.mutations.apply(dedup(self.default));
+        for (_, words) in self.places.iter_mut() {
+            *words = config.mutations.apply(dedup(std::mem::take(words)));
+        }
+
+        self
+    }
+
+    /// the amount of unique parameters within all the wordlists
+    pub fn len(&self) -> usize {
+        self.default
+            .iter()
+            .chain(self.places.values().flatten())
+            .collect::<HashSet<&String>>()
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// returns the wordlist tagged with the request's injection place or the untagged ones otherwise
+    pub fn for_request(&self, request_defaults: &RequestDefaults) -> Vec<String> {
+        let place = match request_defaults.injection_place {
+            InjectionPlace::Path => "query",
+            InjectionPlace::Body if request_defaults.is_json => "json",
+            InjectionPlace::Body => "body",
+            InjectionPlace::Headers => "headers",
+            InjectionPlace::HeaderValue => "header-value",
+        };
+
+        let mut params = match self.places.get(place) {
+            Some(val) => val.clone(),
+            None => self.default.clone(),
+        };
+
+        // such headers usually cause server to timeout
+        // especially when http/2 is used
+        // probably better to add a flag for keeping such parameters?
+        if request_defaults.injection_place == InjectionPlace::Headers
+            || request_defaults.injection_place == InjectionPlace::HeaderValue
+        {
+            params.retain(|x| "content-length" != x.to_lowercase() && "host" != x.to_lowercase());
+        }
+
+        params
+    }
+}
+
+/// removes duplicates while preserving the order
+fn dedup(words: Vec<String>) -> Vec<String> {
+    let mut seen = HashSet::with_capacity(words.len());
+    words.into_iter().filter(|x| seen.insert(x.clone())).collect()
+}
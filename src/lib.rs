@@ -2,6 +2,7 @@ pub mod config;
 pub mod diff;
 pub mod network;
 pub mod runner;
+pub mod scanner;
 pub mod utils;
 
 const RANDOM_CHARSET: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
@@ -1,5 +1,9 @@
+pub mod rate;
+pub mod raw;
+pub mod retry;
 pub mod request;
 pub mod response;
 pub mod utils;
 
+#[allow(clippy::module_inception)]
 mod tests;
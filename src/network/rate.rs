@@ -0,0 +1,104 @@
+use std::{collections::HashMap, error::Error, time::Duration};
+
+use parking_lot::Mutex;
+use tokio::time::Instant;
+
+/// limits the amount of requests across every runner (--rate) and per host (--host-rate).
+/// Requests are spread evenly, so there are no bursts.
+/// Also pauses hosts that limit the rate of requests themselves
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    /// min interval between any two requests
+    global: Option<Duration>,
+
+    /// min interval between two requests to the same host
+    per_host: Option<Duration>,
+
+    /// the time the next request is allowed at
+    global_next: Mutex<Option<Instant>>,
+    hosts_next: Mutex<HashMap<String, Instant>>,
+
+    /// hosts that limited the rate of requests -> the time to resume at
+    paused: Mutex<HashMap<String, Instant>>,
+}
+
+impl RateLimiter {
+    pub fn new(global: Option<Duration>, per_host: Option<Duration>) -> Self {
+        Self {
+            global,
+            per_host,
+            ..Default::default()
+        }
+    }
+
+    /// stops sending requests to the host for some time (the pause can only be extended)
+    pub fn pause(&self, host: &str, duration: Duration) {
+        let until = Instant::now() + duration;
+        let mut paused = self.paused.lock();
+
+        if paused.get(host).map(|x| *x < until).unwrap_or(true) {
+            paused.insert(host.to_string(), until);
+        }
+    }
+
+    /// waits until the request to the host is allowed by both limits and the host isn't paused
+    pub async fn wait(&self, host: &str) {
+        // the pause can be extended while sleeping
+        loop {
+            let until = self.paused.lock().get(host).copied();
+            match until {
+                Some(until) if until > Instant::now() => tokio::time::sleep_until(until).await,
+                _ => break,
+            }
+        }
+
+        if let Some(interval) = self.per_host {
+            let slot = {
+                let mut hosts_next = self.hosts_next.lock();
+                reserve(hosts_next.get(host).copied(), interval, |x| {
+                    hosts_next.insert(host.to_string(), x);
+                })
+            };
+            tokio::time::sleep_until(slot).await;
+        }
+
+        if let Some(interval) = self.global {
+            let slot = {
+                let mut global_next = self.global_next.lock();
+                reserve(*global_next, interval, |x| *global_next = Some(x))
+            };
+            tokio::time::sleep_until(slot).await;
+        }
+    }
+}
+
+/// returns the time the request can be sent at and saves the time for the next one
+fn reserve(next: Option<Instant>, interval: Duration, mut save: impl FnMut(Instant)) -> Instant {
+    let now = Instant::now();
+    let slot = match next {
+        Some(next) if next > now => next,
+        _ => now,
+    };
+
+    save(slot + interval);
+    slot
+}
+
+/// parses rates like 10/s, 300/m, 1000/h or just 10 (per second) to the interval between requests
+pub fn parse_rate(rate: &str) -> Result<Duration, Box<dyn Error>> {
+    let (amount, unit) = rate.split_once('/').unwrap_or((rate, "s"));
+
+    let period = match unit {
+        "s" => 1.,
+        "m" => 60.,
+        "h" => 3600.,
+        _ => Err(format!("Unknown rate unit in {}. Available: s, m, h", rate))?,
+    };
+
+    let amount: f64 = match amount.parse() {
+        Ok(val) if val > 0. => val,
+        _ => Err(format!("Unable to parse the rate: {}. Example: 10/s", rate))?,
+    };
+
+    Ok(Duration::from_secs_f64(period / amount))
+}
@@ -0,0 +1,285 @@
+use std::{convert::TryFrom, error::Error, io::Read, sync::Arc, time::Duration};
+
+use flate2::read::{GzDecoder, ZlibDecoder};
+use lazy_static::lazy_static;
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader},
+    net::TcpStream,
+};
+use tokio_rustls::{
+    rustls::{self, client::ServerCertVerified},
+    TlsConnector,
+};
+use url::Url;
+
+/// the same as reqwest's errors -- needs to be Send in order to be used within async recursive functions
+type RawError = Box<dyn Error + Send + Sync>;
+
+/// status code, headers and http version
+type Head = (u16, Vec<(String, String)>, http::Version);
+
+trait Stream: AsyncRead + AsyncWrite + Unpin + Send {}
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Stream for T {}
+
+/// the request from the file that is sent exactly as it is (except for the injection point)
+#[derive(Debug, Clone, Default)]
+pub struct RawRequest {
+    /// the request file content with the %s injection point
+    pub template: String,
+
+    /// http proxy to tunnel requests through via CONNECT
+    pub proxy: Option<Url>,
+
+    pub timeout: Duration,
+}
+
+/// the response parts needed to create the Response struct
+pub(super) struct RawResponse {
+    pub code: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    pub http_version: http::Version,
+}
+
+impl RawRequest {
+    /// returns \r\n or \n depending on what is used within the template
+    pub fn line_ending(&self) -> &'static str {
+        if self.template.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        }
+    }
+
+    /// replaces the injection point and fixes the content-length header's value (if it's present).
+    /// Everything else stays untouched
+    pub fn render(&self, query: &str, replace_random: impl Fn(&str) -> String) -> String {
+        let request = replace_random(&self.template).replace("%s", query);
+
+        let separator = [self.line_ending(), self.line_ending()].concat();
+        let (head, body) = match request.split_once(&separator) {
+            Some((head, body)) => (head, body),
+            None => return request,
+        };
+
+        let head = head
+            .split(self.line_ending())
+            .map(|line| match line.split_once(':') {
+                Some((k, v)) if k.eq_ignore_ascii_case("content-length") => {
+                    // preserve whitespaces around the value
+                    let whitespaces = &v[..v.len() - v.trim_start().len()];
+                    format!("{}:{}{}", k, whitespaces, body.len())
+                }
+                _ => line.to_string(),
+            })
+            .collect::<Vec<String>>()
+            .join(self.line_ending());
+
+        [head.as_str(), &separator, body].concat()
+    }
+
+    pub(super) async fn send(
+        &self,
+        scheme: &str,
+        host: &str,
+        port: u16,
+        request: &str,
+        is_head: bool,
+    ) -> Result<RawResponse, RawError> {
+        match tokio::time::timeout(self.timeout, self.send_without_timeout(scheme, host, port, request, is_head)).await {
+            Ok(val) => val,
+            Err(_) => Err("Timeout while waiting for the response".into()),
+        }
+    }
+
+    async fn send_without_timeout(
+        &self,
+        scheme: &str,
+        host: &str,
+        port: u16,
+        request: &str,
+        is_head: bool,
+    ) -> Result<RawResponse, RawError> {
+        let mut stream: Box<dyn Stream> = match &self.proxy {
+            Some(proxy) => {
+                // the buffered reader is kept, so nothing the proxy sent after its response is lost
+                let mut stream = BufReader::new(
+                    TcpStream::connect((
+                        proxy.host_str().ok_or("Proxy host missing")?,
+                        proxy.port_or_known_default().unwrap_or(8080),
+                    ))
+                    .await?,
+                );
+
+                // the request line isn't changed to the absolute form, so tunnel is used for plain http as well
+                stream
+                    .write_all(format!("CONNECT {0}:{1} HTTP/1.1\r\nHost: {0}:{1}\r\n\r\n", host, port).as_bytes())
+                    .await?;
+
+                let (code, _, _) = parse_head(&read_head(&mut stream).await?)?;
+                if code != 200 {
+                    Err(format!("The proxy responded with {} to CONNECT", code))?;
+                }
+
+                Box::new(stream)
+            }
+            None => Box::new(TcpStream::connect((host, port)).await?),
+        };
+
+        if scheme == "https" {
+            let server_name = rustls::ServerName::try_from(host)?;
+            stream = Box::new(TlsConnector::from(TLS_CONFIG.clone()).connect(server_name, stream).await?);
+        }
+
+        let mut stream = BufReader::new(stream);
+
+        stream.write_all(request.as_bytes()).await?;
+        stream.flush().await?;
+
+        read_response(&mut stream, is_head).await
+    }
+}
+
+/// invalid certificates are accepted the same way as within the default client
+struct NoCertificateVerification;
+
+impl rustls::client::ServerCertVerifier for NoCertificateVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &rustls::Certificate,
+        _intermediates: &[rustls::Certificate],
+        _server_name: &rustls::ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: std::time::SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+}
+
+lazy_static! {
+    static ref TLS_CONFIG: Arc<rustls::ClientConfig> = Arc::new(
+        rustls::ClientConfig::builder()
+            .with_safe_defaults()
+            .with_custom_certificate_verifier(Arc::new(NoCertificateVerification))
+            .with_no_client_auth()
+    );
+}
+
+/// reads bytes until the empty line
+async fn read_head(stream: &mut (impl AsyncRead + Unpin)) -> Result<Vec<u8>, RawError> {
+    let mut head = Vec::new();
+    let mut byte = [0u8; 1];
+
+    while !(head.ends_with(b"\r\n\r\n") || head.ends_with(b"\n\n")) {
+        if stream.read(&mut byte).await? == 0 {
+            Err("Connection closed before the end of the headers")?;
+        }
+        head.push(byte[0]);
+    }
+
+    Ok(head)
+}
+
+/// returns status code, headers with lowercased names, and http version
+fn parse_head(head: &[u8]) -> Result<Head, RawError> {
+    let head = String::from_utf8_lossy(head);
+    let mut lines = head.lines();
+
+    let status_line = lines.next().ok_or("Empty response")?;
+    let mut status_line = status_line.split(' ');
+
+    let http_version = match status_line.next() {
+        Some("HTTP/1.0") => http::Version::HTTP_10,
+        Some(val) if val.starts_with("HTTP/") => http::Version::HTTP_11,
+        _ => Err("Unable to parse the response's status line")?,
+    };
+
+    let code = status_line
+        .next()
+        .ok_or("Unable to parse the response's status code")?
+        .parse()?;
+
+    let headers = lines
+        .filter_map(|x| x.split_once(':'))
+        .map(|(k, v)| (k.trim().to_lowercase(), v.trim().to_string()))
+        .collect();
+
+    Ok((code, headers, http_version))
+}
+
+async fn read_response(stream: &mut (impl AsyncRead + Unpin), is_head: bool) -> Result<RawResponse, RawError> {
+    let (mut code, mut headers, mut http_version) = parse_head(&read_head(stream).await?)?;
+
+    // skip 100 Continue and other informational responses
+    while (100..200).contains(&code) {
+        (code, headers, http_version) = parse_head(&read_head(stream).await?)?;
+    }
+
+    let header = |name: &str| headers.iter().find(|x| x.0 == name).map(|x| x.1.to_lowercase());
+
+    let body = if is_head || code == 204 || code == 304 {
+        Vec::new()
+    } else if header("transfer-encoding").map(|x| x.contains("chunked")) == Some(true) {
+        read_chunked(stream).await?
+    } else if let Some(length) = header("content-length") {
+        let mut body = vec![0u8; length.parse()?];
+        stream.read_exact(&mut body).await?;
+        body
+    } else {
+        let mut body = Vec::new();
+        stream.read_to_end(&mut body).await?;
+        body
+    };
+
+    let body = match header("content-encoding").as_deref() {
+        Some("gzip") => decompress(GzDecoder::new(body.as_slice()))?,
+        Some("deflate") => decompress(ZlibDecoder::new(body.as_slice()))?,
+        _ => body,
+    };
+
+    Ok(RawResponse {
+        code,
+        headers,
+        body,
+        http_version,
+    })
+}
+
+async fn read_chunked(stream: &mut (impl AsyncRead + Unpin)) -> Result<Vec<u8>, RawError> {
+    let mut body = Vec::new();
+
+    loop {
+        let mut line = Vec::new();
+        let mut byte = [0u8; 1];
+
+        while !line.ends_with(b"\n") {
+            if stream.read(&mut byte).await? == 0 {
+                Err("Connection closed within the chunked body")?;
+            }
+            line.push(byte[0]);
+        }
+
+        // chunk extensions are separated by ;
+        let size = String::from_utf8_lossy(&line);
+        let size = usize::from_str_radix(size.trim().split(';').next().unwrap_or(""), 16)?;
+
+        if size == 0 {
+            // trailers aren't needed
+            break;
+        }
+
+        let mut chunk = vec![0u8; size + 2];
+        stream.read_exact(&mut chunk).await?;
+        chunk.truncate(size);
+        body.append(&mut chunk);
+    }
+
+    Ok(body)
+}
+
+fn decompress(mut decoder: impl Read) -> Result<Vec<u8>, RawError> {
+    let mut decompressed = Vec::new();
+    decoder.read_to_end(&mut decompressed)?;
+    Ok(decompressed)
+}
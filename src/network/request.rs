@@ -1,4 +1,4 @@
-use crate::{config::structs::Config, utils::random_line, VALUE_LENGTH, RANDOM_LENGTH};
+use crate::{config::structs::{Config, Target}, utils::{fnv_hash, random_line}, VALUE_LENGTH, RANDOM_LENGTH};
 use itertools::Itertools;
 use lazy_static::lazy_static;
 use percent_encoding::utf8_percent_encode;
@@ -9,6 +9,7 @@ use std::{
     convert::TryFrom,
     error::Error,
     iter::FromIterator,
+    sync::Arc,
     time::{Duration, Instant},
 };
 use url::Url;
@@ -19,8 +20,11 @@ const HEADERS_MIDDLE: &str = "\x00@%=%@\x00";
 const HEADERS_JOINER: &str = "\x01@%&%@\x01";
 
 use super::{
+    rate::RateLimiter,
+    raw::RawRequest,
+    retry::{Failures, RetryPolicy},
     response::Response,
-    utils::{DataType, Headers, InjectionPlace, FRAGMENT, create_client, is_binary_content},
+    utils::{DataType, Headers, InjectionPlace, FRAGMENT, create_client, is_binary_content, is_body_method},
 };
 
 #[derive(Debug, Clone, Default)]
@@ -63,6 +67,9 @@ pub struct RequestDefaults {
     /// it is used in recursion search
     pub parameters: Vec<(String, String)>,
 
+    /// parameters with json objects or arrays as values. They are added to json bodies without quotes
+    pub raw_parameters: Vec<String>,
+
     /// where the injection point is
     pub injection_place: InjectionPlace,
 
@@ -71,6 +78,20 @@ pub struct RequestDefaults {
 
     /// check body of responses with binary content type
     pub check_binary: bool,
+
+    /// the request file to send as it is (verbatim mode)
+    pub raw_request: Option<RawRequest>,
+
+    /// shared between every runner and the replay client
+    pub rate_limiter: Arc<RateLimiter>,
+
+    pub retry_policy: RetryPolicy,
+
+    /// failed requests of the url:method pair
+    pub failures: Arc<Failures>,
+
+    /// either --http or the version of the imported request
+    pub http_version: Option<http::Version>,
 }
 
 #[derive(Debug, Clone)]
@@ -98,6 +119,9 @@ pub struct Request<'a> {
     /// whether the request was prepared
     /// {{random}} things replaced, prepared_parameters filled
     pub prepared: bool,
+
+    /// the exact request to send in the verbatim mode
+    pub raw: Option<String>,
 }
 
 impl<'a> Request<'a> {
@@ -111,6 +135,7 @@ impl<'a> Request<'a> {
             prepared_parameters: Vec::new(), //l.parameters.clone(),
             non_random_parameters: Vec::new(),
             prepared: false,
+            raw: None,
         }
     }
 
@@ -149,7 +174,7 @@ impl<'a> Request<'a> {
                 // not very optimal because we know that there's a lot of random parameters
                 // that doesn't need to be checked
                 .map(|(k, v)| {
-                    if RE_JSON_WORDS_WITHOUT_QUOTES.is_match(v) {
+                    if RE_JSON_WORDS_WITHOUT_QUOTES.is_match(v) || self.defaults.raw_parameters.contains(k) {
                         self.defaults.template.replace("%k", k).replace("%v", v)
                     } else {
                         self.defaults
@@ -282,14 +307,44 @@ impl<'a> Request<'a> {
                 self.set_headers(headers);
             }
         }
+
+        if let Some(raw_request) = &self.defaults.raw_request {
+            let query = if self.defaults.injection_place == InjectionPlace::Headers {
+                self.make_query()
+                    .split(&self.defaults.joiner)
+                    .filter(|x| !x.is_empty())
+                    .map(|x| x.replace(HEADERS_MIDDLE, ": "))
+                    .collect::<Vec<String>>()
+                    .join(raw_request.line_ending())
+            } else {
+                self.make_query()
+            };
+
+            self.raw = Some(raw_request.render(&query, |x| x.replace("{{random}}", &random_line(RANDOM_LENGTH))));
+        }
     }
 
+    /// retries the request according to the retry policy (--retries, --retry-on)
     pub async fn send_by(self, clients: &Client) -> Result<Response<'a>, Box<dyn Error>> {
-        match self.clone().request(clients).await {
-            Ok(val) => Ok(val),
-            Err(_) => {
-                tokio::time::sleep(Duration::from_secs(10)).await;
-                Ok(self.clone().request(clients).await?)
+        let defaults = self.defaults;
+        let mut attempt = 0;
+
+        loop {
+            let err = match self.clone().request(clients).await {
+                Ok(val) => return Ok(val),
+                Err(err) => err,
+            };
+
+            match defaults.retry_policy.backoff(attempt, err.as_ref()) {
+                Some(delay) => {
+                    defaults.failures.retried();
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                None => {
+                    defaults.failures.failed();
+                    return Err(err);
+                }
             }
         }
     }
@@ -309,9 +364,14 @@ impl<'a> Request<'a> {
         self.send_by(dc).await
     }
 
-    async fn request(mut self, client: &Client) -> Result<Response<'a>, reqwest::Error> {
+    async fn request(mut self, client: &Client) -> Result<Response<'a>, Box<dyn Error + Send + Sync>> {
         self.prepare();
 
+        let defaults = self.defaults;
+        if let Some(raw_request) = &defaults.raw_request {
+            return self.request_raw(raw_request).await;
+        }
+
         let mut request = http::Request::builder()
             .method(self.defaults.method.as_str())
             .uri(self.url());
@@ -323,6 +383,7 @@ impl<'a> Request<'a> {
         let request = request.body(self.body.to_owned()).unwrap();
 
         tokio::time::sleep(self.defaults.delay).await;
+        self.defaults.rate_limiter.wait(&self.defaults.host).await;
 
         let reqwest_req = reqwest::Request::try_from(request).unwrap();
 
@@ -376,6 +437,47 @@ impl<'a> Request<'a> {
         Ok(response)
     }
 
+    /// sends the request exactly as it is within the request file
+    async fn request_raw(self, raw_request: &RawRequest) -> Result<Response<'a>, Box<dyn Error + Send + Sync>> {
+        tokio::time::sleep(self.defaults.delay).await;
+        self.defaults.rate_limiter.wait(&self.defaults.host).await;
+
+        let start = Instant::now();
+
+        let raw_response = raw_request
+            .send(
+                &self.defaults.scheme,
+                &self.defaults.host,
+                self.defaults.port,
+                self.raw.as_ref().unwrap(),
+                self.defaults.method == "HEAD",
+            )
+            .await?;
+
+        let duration = start.elapsed();
+
+        let text = if is_binary_content(raw_response.headers.get_value_case_insensitive("content-type")) && !self.defaults.check_binary {
+            String::new()
+        } else {
+            String::from_utf8_lossy(&raw_response.body).to_string()
+        };
+
+        let mut response = Response {
+            code: raw_response.code,
+            headers: raw_response.headers,
+            time: duration.as_millis(),
+            text,
+            request: Some(self),
+            reflected_parameters: HashMap::new(),
+            http_version: Some(raw_response.http_version),
+        };
+
+        response.beautify_body();
+        response.add_headers();
+
+        Ok(response)
+    }
+
     /// the function is used when there was a error during the request
     pub fn empty_response(mut self) -> Response<'a> {
         self.prepare();
@@ -396,6 +498,10 @@ impl<'a> Request<'a> {
     }
 
     pub fn print_sent(&self) -> String {
+        if let Some(raw) = &self.raw {
+            return raw.to_owned();
+        }
+
         let host = if self.headers.contains_key("Host") {
             self.headers.get_value("Host").unwrap()
         } else {
@@ -417,32 +523,161 @@ impl<'a> Request<'a> {
 
         str_req
     }
+
+    /// the curl command that sends the same request
+    pub fn curl(&self) -> String {
+        let port = if self.defaults.port == 443 || self.defaults.port == 80 {
+            String::new()
+        } else {
+            format!(":{}", self.defaults.port)
+        };
+
+        let base_url = format!("{}://{}{}", self.defaults.scheme, self.defaults.host, port);
+
+        let (method, url, headers, body) = match &self.raw {
+            Some(raw) => {
+                let (method, path, headers, body) = self.split_raw(raw);
+                (method, base_url + &path, headers, body)
+            }
+            None => (
+                self.defaults.method.clone(),
+                base_url + &self.path,
+                self.headers.iter().sorted().cloned().collect(),
+                self.body.clone(),
+            ),
+        };
+
+        let mut command = if method == "HEAD" {
+            format!("curl -I {}", shell_quote(&url))
+        } else {
+            format!("curl -X {} {}", method, shell_quote(&url))
+        };
+
+        // the certificates aren't verified by x8 as well
+        if self.defaults.scheme == "https" {
+            command += " -k";
+        }
+
+        match self.defaults.http_version {
+            Some(http::Version::HTTP_11) => command += " --http1.1",
+            Some(http::Version::HTTP_2) => command += " --http2-prior-knowledge",
+            _ => (),
+        }
+
+        for (k, v) in headers.iter() {
+            command += &format!(" -H {}", shell_quote(&format!("{}: {}", k, v)));
+        }
+
+        // the verbatim request shouldn't contain the headers curl adds by default
+        if self.raw.is_some() {
+            let mut defaults = vec!["User-Agent", "Accept"];
+            if !body.is_empty() {
+                defaults.push("Content-Type");
+            }
+
+            for default in defaults {
+                if headers.get_index_case_insensitive(default).is_none() {
+                    command += &format!(" -H '{}:'", default);
+                }
+            }
+        }
+
+        if !body.is_empty() {
+            command += &format!(" --data-raw {}", shell_quote(&body));
+        }
+
+        command
+    }
+
+    /// returns the method, path, headers and body of the verbatim request.
+    /// Content-Length is skipped because curl sets it on its own
+    fn split_raw(&self, raw: &str) -> (String, String, Vec<(String, String)>, String) {
+        let line_ending = match &self.defaults.raw_request {
+            Some(raw_request) => raw_request.line_ending(),
+            None => "\n",
+        };
+
+        let (head, body) = raw
+            .split_once(&[line_ending, line_ending].concat())
+            .unwrap_or((raw, ""));
+
+        let mut lines = head.split(line_ending);
+
+        let mut firstline = lines.next().unwrap_or("").split(' ');
+        let method = firstline.next().unwrap_or("").to_string();
+        let path = firstline.next().unwrap_or("").to_string();
+
+        let headers = lines
+            .filter_map(|x| x.split_once(':'))
+            .filter(|(k, _)| !k.eq_ignore_ascii_case("content-length"))
+            .map(|(k, v)| (k.to_string(), v.trim().to_string()))
+            .collect();
+
+        (method, path, headers, body.to_string())
+    }
+}
+
+/// wraps the string in single quotes so the shell doesn't interpret it
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
 }
 
 impl<'a> RequestDefaults {
-    pub fn from_config<S: Into<String>>(
+    pub fn from_config(
         config: &Config,
-        method: S,
-        url: S,
+        target: &Target,
     ) -> Result<Self, Box<dyn Error>> {
-        Self::new(
-            method.into().as_str(), //method needs to be set explicitly via .set_method()
-            url.into().as_str(),    //as well as url
-            config.custom_headers.clone(),
+        let http_version = config.http_version.or(target.http_version);
+
+        let mut request_defaults = Self::new(
+            &target.method,
+            &target.url,
+            target.headers.clone(),
             config.delay,
-            create_client(config, false)?,
+            create_client(config, http_version, false)?,
             config.template.clone(),
             config.joiner.clone(),
             config.encode,
-            config.data_type.clone(),
-            config.invert,
+            target.data_type.clone(),
+            config.invert || target.invert,
             config.headers_discovery,
-            &config.body,
+            &target.body,
             config.disable_custom_parameters,
             config.check_binary
-        )
+        )?;
+
+        request_defaults.rate_limiter = Arc::clone(&config.rate_limiter);
+        request_defaults.retry_policy = config.retry_policy.clone();
+        request_defaults.http_version = http_version;
+
+        // documented parameters would be sent as headers otherwise
+        if !config.headers_discovery {
+            request_defaults.parameters = target.parameters.clone();
+            request_defaults.raw_parameters = target.raw_parameters.clone();
+        }
+
+        if let Some(raw) = &target.raw {
+            let proxy = if config.proxy.is_empty() {
+                None
+            } else {
+                let proxy = Url::parse(&config.proxy)?;
+                if proxy.scheme() != "http" {
+                    Err("Only http proxies are supported in the verbatim mode")?;
+                }
+                Some(proxy)
+            };
+
+            request_defaults.raw_request = Some(RawRequest {
+                template: raw.to_owned(),
+                proxy,
+                timeout: Duration::from_secs(config.timeout as u64),
+            });
+        }
+
+        Ok(request_defaults)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn new<S: Into<String> + From<String> + std::fmt::Debug>(
         method: &str,
         url: &str,
@@ -462,8 +697,7 @@ impl<'a> RequestDefaults {
 
         let mut injection_place = if headers_discovery {
             InjectionPlace::Headers
-        } else if (method == "POST" || method == "PUT" || method == "PATCH" || method == "DELETE") && !invert
-        || (method != "POST" && method != "PUT" && method != "PATCH" && method != "DELETE" && invert) {
+        } else if is_body_method(method) != invert {
             InjectionPlace::Body
         } else {
             InjectionPlace::Path
@@ -541,7 +775,19 @@ impl<'a> RequestDefaults {
 
             parameters: Vec::new(),
 
-            check_binary
+            raw_parameters: Vec::new(),
+
+            check_binary,
+
+            raw_request: None,
+
+            rate_limiter: Default::default(),
+
+            retry_policy: Default::default(),
+
+            failures: Default::default(),
+
+            http_version: None,
         })
     }
 
@@ -630,6 +876,20 @@ impl<'a> RequestDefaults {
         format!("{}://{}:{}{}", self.scheme, self.host, self.port, self.path)
     }
 
+    /// hash of the headers, body and injection place.
+    /// Distinguishes targets that share the method and the url
+    pub fn target_hash(&self) -> u64 {
+        let injection_place = format!("{:?}", self.injection_place);
+        let raw_request = self.raw_request.as_ref().map(|x| x.template.as_str()).unwrap_or_default();
+
+        fnv_hash(
+            self.custom_headers
+                .iter()
+                .flat_map(|(k, v)| [k.as_str(), v.as_str()])
+                .chain([self.body.as_str(), injection_place.as_str(), raw_request]),
+        )
+    }
+
     /// recreates url without default port
     pub fn url_without_default_port(&self) -> String {
         let port = if self.port == 443 || self.port == 80 {
@@ -1,16 +1,15 @@
-use std::{collections::HashMap, error::Error, iter::FromIterator, io::{self, Write}};
+use std::{collections::HashMap, error::Error, iter::FromIterator, time::Duration};
 
 use colored::Colorize;
-use indicatif::ProgressBar;
 use itertools::Itertools;
 use lazy_static::lazy_static;
 use regex::Regex;
 
-use crate::{config::structs::Config, diff::diff, runner::utils::ReasonKind, utils::{color_id, is_id_important}};
+use crate::diff::diff;
 
 use super::{
     request::Request,
-    utils::{save_request, Headers},
+    utils::Headers,
 };
 
 #[derive(Debug, Clone, Default)]
@@ -51,7 +50,65 @@ pub enum Status {
     Other,
 }
 
+impl Status {
+    pub fn of(code: u16) -> Self {
+        if code <= 199 {
+            Status::Other
+        } else if code <= 299 {
+            Status::Ok
+        } else if code <= 399 {
+            Status::Redirect
+        } else if code <= 499 {
+            Status::UserFault
+        } else if code <= 599 {
+            Status::ServerFault
+        } else {
+            Status::Other
+        }
+    }
+}
+
+/// returns the code with colors depending on its kind
+pub fn colored_code(code: u16) -> String {
+    match Status::of(code) {
+        Status::Ok => code.to_string().bright_green().to_string(),
+        Status::Redirect => code.to_string().bright_blue().to_string(),
+        Status::UserFault => code.to_string().bright_yellow().to_string(),
+        Status::ServerFault => code.to_string().bright_red().to_string(),
+        Status::Other => code.to_string().magenta().to_string(),
+    }
+}
+
 impl<'a> Response<'a> {
+    /// returns the reason in case the response looks like the server limits the rate of requests.
+    /// Responses that are the same as the initial one in this regard aren't counted
+    pub fn rate_limit_reason(&self, initial_response: &Response, signatures: &[Regex]) -> Option<String> {
+        if self.code != initial_response.code {
+            if self.code == 429 {
+                return Some("429 Too Many Requests".to_string());
+            }
+
+            if self.code == 503 && self.headers.get_index_case_insensitive("retry-after").is_some() {
+                return Some("503 with Retry-After".to_string());
+            }
+        }
+
+        signatures
+            .iter()
+            .find(|x| x.is_match(&self.text) && !x.is_match(&initial_response.text))
+            .map(|x| format!("{} within the body", x.as_str()))
+    }
+
+    /// the delay from the Retry-After header. Only the amount of seconds is supported
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.headers
+            .get_value_case_insensitive("retry-after")?
+            .trim()
+            .parse()
+            .ok()
+            .map(Duration::from_secs)
+    }
+
     /// count how many times we can see the string in the response
     pub fn count(&self, string: &str) -> usize {
         let re = Regex::new(&format!("(?i){}", string)).unwrap();
@@ -231,96 +288,9 @@ impl<'a> Response<'a> {
         self.text = text + "\n" + &self.text;
     }
 
-    /// write about found parameter to stdout and save when needed
-    pub fn write_and_save(
-        &self,
-        id: usize,
-        config: &Config,
-        initial_response: &Response,
-        reason_kind: ReasonKind,
-        parameter: &str,
-        diff: Option<&str>,
-        progress_bar: &ProgressBar,
-    ) -> Result<(), Box<dyn Error>> {
-
-        let id_if_important = if is_id_important(config) {
-            format!("{}) ", color_id(id))
-        } else {
-            String::new()
-        };
-
-        let mut message = match reason_kind {
-            ReasonKind::Code => format!(
-                "{}{}: code {} -> {}",
-                &id_if_important,
-                &parameter,
-                initial_response.code(),
-                self.code(),
-            ),
-            ReasonKind::Text => format!(
-                "{}{}: page {} -> {} ({})",
-                &id_if_important,
-                &parameter,
-                initial_response.text.len(),
-                self.text.len().to_string().bright_yellow(),
-                diff.unwrap()
-            ),
-            ReasonKind::Reflected => format!(
-                "{}{}: {}",
-                &id_if_important,
-                "reflects".bright_blue(),
-                parameter
-            ),
-            ReasonKind::NotReflected => format!(
-                "{}{}: {}",
-                &id_if_important,
-                "changes reflections".bright_cyan(),
-                parameter
-            ),
-        };
-
-        if config.verbose > 0 {
-            if !config.save_responses.is_empty() {
-                message += &format!(" [saved to {}]", save_request(config, self, parameter)?);
-            }
-
-            if config.disable_progress_bar {
-                writeln!(io::stdout(), "{}", message).ok();
-            } else {
-                progress_bar.println(message);
-            }
-        } else if !config.save_responses.is_empty() {
-            save_request(config, self, parameter)?;
-        }
-
-        Ok(())
-    }
-
-    fn kind(&self) -> Status {
-        if self.code <= 199 {
-            Status::Other
-        } else if self.code <= 299 {
-            Status::Ok
-        } else if self.code <= 399 {
-            Status::Redirect
-        } else if self.code <= 499 {
-            Status::UserFault
-        } else if self.code <= 599 {
-            Status::ServerFault
-        } else {
-            Status::Other
-        }
-    }
-
     /// returns self.code but with colors
     pub fn code(&self) -> String {
-        match self.kind() {
-            Status::Ok => self.code.to_string().bright_green().to_string(),
-            Status::Redirect => self.code.to_string().bright_blue().to_string(),
-            Status::UserFault => self.code.to_string().bright_yellow().to_string(),
-            Status::ServerFault => self.code.to_string().bright_red().to_string(),
-            Status::Other => self.code.to_string().magenta().to_string(),
-        }
+        colored_code(self.code)
     }
 
     /// get possible parameters from the page itself
@@ -0,0 +1,146 @@
+use std::{
+    error::Error,
+    str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use rand::Rng;
+
+/// the delay before the first retry. Doubles with every next one
+const BASE_DELAY: Duration = Duration::from_secs(1);
+
+const MAX_DELAY: Duration = Duration::from_secs(60);
+
+/// kinds of request errors that can be retried (--retry-on)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+    Timeout,
+    Reset,
+    Tls,
+    Dns,
+    Other,
+}
+
+impl FromStr for ErrorClass {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "timeout" => Ok(Self::Timeout),
+            "reset" => Ok(Self::Reset),
+            "tls" => Ok(Self::Tls),
+            "dns" => Ok(Self::Dns),
+            "other" => Ok(Self::Other),
+            _ => Err(format!("Unknown error class: {}", s)),
+        }
+    }
+}
+
+impl ErrorClass {
+    /// guesses the class by the error and its sources because
+    /// the errors come from reqwest, hyper, rustls and the raw client
+    pub fn of(err: &(dyn Error + 'static)) -> Self {
+        let mut messages = Vec::new();
+        let mut source = Some(err);
+
+        while let Some(err) = source {
+            if let Some(err) = err.downcast_ref::<reqwest::Error>() {
+                if err.is_timeout() {
+                    return Self::Timeout;
+                }
+            }
+
+            if let Some(err) = err.downcast_ref::<std::io::Error>() {
+                match err.kind() {
+                    std::io::ErrorKind::TimedOut => return Self::Timeout,
+                    std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::BrokenPipe
+                    | std::io::ErrorKind::UnexpectedEof => return Self::Reset,
+                    _ => (),
+                }
+            }
+
+            messages.push(err.to_string().to_lowercase());
+            source = err.source();
+        }
+
+        let message = messages.join(": ");
+        let contains = |words: &[&str]| words.iter().any(|x| message.contains(x));
+
+        if contains(&["timed out", "timeout"]) {
+            Self::Timeout
+        } else if contains(&["dns", "lookup address", "no record found", "resolve"]) {
+            Self::Dns
+        } else if contains(&["tls", "ssl", "certificate", "handshake"]) {
+            Self::Tls
+        } else if contains(&["reset", "broken pipe", "connection closed", "incomplete message", "unexpected eof"]) {
+            Self::Reset
+        } else {
+            Self::Other
+        }
+    }
+}
+
+/// how to retry failed requests (--retries, --retry-on)
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub retries: usize,
+
+    /// only errors of these classes are retried
+    pub classes: Vec<ErrorClass>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            retries: 1,
+            classes: vec![
+                ErrorClass::Timeout,
+                ErrorClass::Reset,
+                ErrorClass::Tls,
+                ErrorClass::Dns,
+                ErrorClass::Other,
+            ],
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// returns the delay before the retry or None in case the request shouldn't be retried
+    pub fn backoff(&self, attempt: usize, err: &(dyn Error + 'static)) -> Option<Duration> {
+        if attempt >= self.retries || !self.classes.contains(&ErrorClass::of(err)) {
+            return None;
+        }
+
+        let delay = BASE_DELAY
+            .checked_mul(2u32.saturating_pow(attempt as u32))
+            .unwrap_or(MAX_DELAY)
+            .min(MAX_DELAY);
+
+        // up to 50% of jitter so concurrent requests don't retry at the same time
+        let jitter = rand::thread_rng().gen_range(0., 0.5);
+
+        Some(delay + delay.mul_f64(jitter))
+    }
+}
+
+/// failed requests of a single runner
+#[derive(Debug, Default)]
+pub struct Failures {
+    pub retries: AtomicUsize,
+
+    /// requests that failed even after all the retries
+    pub failed: AtomicUsize,
+}
+
+impl Failures {
+    pub fn retried(&self) {
+        self.retries.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn failed(&self) {
+        self.failed.fetch_add(1, Ordering::Relaxed);
+    }
+}
@@ -1,9 +1,14 @@
 #[cfg(test)]
 mod tests {
+    use regex::Regex;
     use tokio::time::Duration;
 
     use crate::network::{
+        rate::{parse_rate, RateLimiter},
+        raw::RawRequest,
         request::{Request, RequestDefaults},
+        response::Response,
+        retry::{ErrorClass, RetryPolicy},
         utils::{Headers, InjectionPlace, is_binary_content},
     };
 
@@ -20,9 +25,11 @@ mod tests {
 
     #[test]
     fn query_creation() {
-        let mut l = RequestDefaults::default();
-        l.template = "%k=payload".to_string();
-        l.joiner = "&".to_string();
+        let l = RequestDefaults {
+            template: "%k=payload".to_string(),
+            joiner: "&".to_string(),
+            ..Default::default()
+        };
         let parameters = vec!["test1".to_string()];
         let mut request = Request::new(&l, parameters);
         request.prepare();
@@ -63,6 +70,37 @@ mod tests {
         assert_eq!(defaults.injection_place, InjectionPlace::Path);
     }
 
+    #[test]
+    fn curl_command() {
+        let mut defaults = RequestDefaults::new::<String>(
+            "POST",
+            "https://example.com/api",
+            Vec::from([("X-Header".to_string(), "it's".to_string())]),
+            Duration::from_millis(0),
+            Default::default(),
+            None,
+            None,
+            false,
+            None,
+            false,
+            false,
+            "a=1",
+            false,
+            false,
+        )
+        .unwrap();
+        defaults.http_version = Some(http::Version::HTTP_2);
+
+        let mut request = Request::new(&defaults, vec!["debug=true".to_string()]);
+        request.prepare();
+
+        assert_eq!(
+            request.curl(),
+            "curl -X POST 'https://example.com/api' -k --http2-prior-knowledge \
+            -H 'Content-Type: application/x-www-form-urlencoded' -H 'X-Header: it'\\''s' --data-raw 'a=1&debug=true'"
+        );
+    }
+
     #[test]
     fn json_request_body_generation() {
         let defaults = RequestDefaults::new::<String>(
@@ -84,7 +122,137 @@ mod tests {
         .unwrap();
 
         assert!(defaults.is_json);
-        assert_eq!(defaults.body, "{\"something\":1, %s}");
-        assert_eq!(defaults.template, "\"%k\": %v");
+        assert_eq!(defaults.body, "{\"something\":1,%s}");
+        assert_eq!(defaults.template, "\"%k\":%v");
+    }
+
+    #[test]
+    fn json_raw_parameters() {
+        let l = RequestDefaults {
+            template: "\"%k\":%v".to_string(),
+            joiner: ",".to_string(),
+            is_json: true,
+            parameters: vec![
+                ("tags".to_string(), "[\"a\"]".to_string()),
+                ("filter".to_string(), "{\"b\":1}".to_string()),
+            ],
+            raw_parameters: vec!["tags".to_string()],
+            ..Default::default()
+        };
+
+        let request = Request::new(&l, Vec::new());
+
+        assert_eq!(
+            request.make_query(),
+            "\"tags\":[\"a\"],\"filter\":\"{\"b\":1}\""
+        );
+    }
+
+    #[test]
+    fn verbatim_request_rendering() {
+        let l = RequestDefaults {
+            scheme: "http".to_string(),
+            host: "example.com".to_string(),
+            port: 80,
+            template: "%k=%v".to_string(),
+            joiner: "&".to_string(),
+            injection_place: InjectionPlace::Body,
+            raw_request: Some(RawRequest {
+                template: "POST /login HTTP/1.1\r\nhost: example.com\r\nX-A: 1\r\nX-A: 2\r\ncontent-length:  4\r\n\r\na=b&%s".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let mut request = Request::new(&l, vec!["user=admin".to_string()]);
+        request.prepare();
+
+        assert_eq!(
+            request.print_sent(),
+            "POST /login HTTP/1.1\r\nhost: example.com\r\nX-A: 1\r\nX-A: 2\r\ncontent-length:  14\r\n\r\na=b&user=admin"
+        );
+
+        // headers are kept in the same order, while the ones curl adds by default are removed
+        let mut request = Request::new(&l, vec!["user=admin".to_string()]);
+        request.prepare();
+
+        assert_eq!(
+            request.curl(),
+            "curl -X POST 'http://example.com/login' -H 'host: example.com' -H 'X-A: 1' -H 'X-A: 2' \
+            -H 'User-Agent:' -H 'Accept:' -H 'Content-Type:' --data-raw 'a=b&user=admin'"
+        );
+    }
+
+    #[tokio::test]
+    async fn rate_limits() {
+        assert_eq!(parse_rate("10/s").unwrap(), Duration::from_millis(100));
+        assert_eq!(parse_rate("120/m").unwrap(), Duration::from_millis(500));
+        assert_eq!(parse_rate("4").unwrap(), Duration::from_millis(250));
+        assert!(parse_rate("0/s").is_err());
+        assert!(parse_rate("10/d").is_err());
+
+        // 4 requests to the same host at 20/s and 2 requests to another host
+        let limiter = RateLimiter::new(None, Some(Duration::from_millis(50)));
+        let start = tokio::time::Instant::now();
+        for host in ["a", "a", "b", "a", "b", "a"] {
+            limiter.wait(host).await;
+        }
+        assert!(start.elapsed() >= Duration::from_millis(150));
+    }
+
+    #[test]
+    fn rate_limit_responses() {
+        let initial_response = Response {
+            code: 200,
+            text: "<html>captcha</html>".to_string(),
+            ..Default::default()
+        };
+        let signatures = vec![Regex::new("(?i)too many requests").unwrap(), Regex::new("captcha").unwrap()];
+
+        let response = |code: u16, headers: Vec<(&str, &str)>, text: &str| Response {
+            code,
+            headers: headers.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            text: text.to_string(),
+            ..Default::default()
+        };
+
+        let limited = response(429, vec![("Retry-After", "7")], "");
+        assert!(limited.rate_limit_reason(&initial_response, &signatures).is_some());
+        assert_eq!(limited.retry_after(), Some(Duration::from_secs(7)));
+
+        assert!(response(503, vec![("retry-after", "1")], "").rate_limit_reason(&initial_response, &[]).is_some());
+        assert!(response(503, vec![], "").rate_limit_reason(&initial_response, &[]).is_none());
+        assert!(response(200, vec![], "Too Many Requests").rate_limit_reason(&initial_response, &signatures).is_some());
+
+        // the initial response contains the signature as well
+        assert!(response(200, vec![], "captcha").rate_limit_reason(&initial_response, &signatures).is_none());
+        assert!(response(200, vec![], "ok").rate_limit_reason(&initial_response, &signatures).is_none());
+    }
+
+    #[test]
+    fn retry_policy() {
+        let reset: Box<dyn std::error::Error> =
+            Box::new(std::io::Error::new(std::io::ErrorKind::ConnectionReset, "reset by peer"));
+        let dns: Box<dyn std::error::Error> = "error trying to connect: dns error: no record found".into();
+        let tls: Box<dyn std::error::Error> = "invalid peer certificate: UnknownIssuer".into();
+        let timeout: Box<dyn std::error::Error> = "Timeout while waiting for the response".into();
+
+        assert_eq!(ErrorClass::of(reset.as_ref()), ErrorClass::Reset);
+        assert_eq!(ErrorClass::of(dns.as_ref()), ErrorClass::Dns);
+        assert_eq!(ErrorClass::of(tls.as_ref()), ErrorClass::Tls);
+        assert_eq!(ErrorClass::of(timeout.as_ref()), ErrorClass::Timeout);
+
+        let policy = RetryPolicy {
+            retries: 3,
+            classes: vec![ErrorClass::Reset],
+        };
+
+        // 1s, 2s, 4s with up to 50% of jitter
+        for (attempt, secs) in [1, 2, 4].iter().enumerate() {
+            let delay = policy.backoff(attempt, reset.as_ref()).unwrap();
+            assert!(delay >= Duration::from_secs(*secs) && delay <= Duration::from_millis(secs * 1500));
+        }
+        assert!(policy.backoff(3, reset.as_ref()).is_none());
+        assert!(policy.backoff(0, dns.as_ref()).is_none());
     }
 }
@@ -4,7 +4,7 @@ use lazy_static::lazy_static;
 use percent_encoding::{AsciiSet, CONTROLS};
 use regex::Regex;
 use reqwest::Client;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::{config::structs::Config, utils::random_line};
 
@@ -44,18 +44,15 @@ pub enum DataType {
 }
 
 /// where to insert parameters
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Copy)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Copy, Default)]
 pub enum InjectionPlace {
+    #[default]
     Path,
     Body,
     Headers,
     HeaderValue,
 }
 
-impl Default for InjectionPlace {
-    fn default() -> Self { InjectionPlace::Path }
-}
-
 pub trait Headers {
     fn contains_key(&self, key: &str) -> bool;
     fn get_index_case_insensitive(&self, key: &str) -> Option<usize>;
@@ -99,7 +96,7 @@ impl Headers for Vec<(String, String)> {
 
 /// writes request and response to a file
 /// return file location
-pub(super) fn save_request(
+pub(crate) fn save_request(
     config: &Config,
     response: &Response,
     param_key: &str,
@@ -126,7 +123,11 @@ pub(super) fn save_request(
     Ok(filename)
 }
 
-pub fn create_client(config: &Config, replay: bool) -> Result<Client, Box<dyn Error>> {
+pub fn create_client(
+    config: &Config,
+    http_version: Option<http::Version>,
+    replay: bool,
+) -> Result<Client, Box<dyn Error>> {
     let mut client = Client::builder()
         .danger_accept_invalid_certs(true)
         .timeout(Duration::from_secs(config.timeout as u64))
@@ -156,8 +157,8 @@ pub fn create_client(config: &Config, replay: bool) -> Result<Client, Box<dyn Er
         client = client.redirect(reqwest::redirect::Policy::none());
     }
 
-    if config.http_version.is_some() {
-        match config.http_version {
+    if http_version.is_some() {
+        match http_version {
             Some(http::Version::HTTP_11) => client = client.http1_only(),
             Some(http::Version::HTTP_2) => client = client.http2_prior_knowledge(),
             _ => unreachable!()
@@ -168,11 +169,16 @@ pub fn create_client(config: &Config, replay: bool) -> Result<Client, Box<dyn Er
 }
 
 /// check whether the content is binary
+/// parameters are sent within the body by default only for these methods
+pub fn is_body_method(method: &str) -> bool {
+    ["POST", "PUT", "PATCH", "DELETE"].contains(&method)
+}
+
 /// so we can ignore the body in comparing
 /// a few reasons for it:
 /// 1. the comparing of binary content takes a lot of time
 /// 2. page diff anyway will be checked by the content-length header
-/// because the content-length header usually static for binary files
+///    because the content-length header usually static for binary files
 pub fn is_binary_content(content_type: Option<String>) -> bool {
     lazy_static!{
         static ref RE_BINARY_MIME: Regex = Regex::new(
@@ -0,0 +1,115 @@
+use std::{collections::HashMap, error::Error, fs};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    output::RunnerOutput,
+    utils::{FoundParameter, Parameters},
+};
+
+/// the parameter compared to the previous scan (--baseline)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BaselineStatus {
+    /// wasn't found within the previous scan
+    New,
+
+    /// was found within both scans
+    Present,
+
+    /// was found only within the previous scan
+    Gone,
+}
+
+impl BaselineStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::New => "new",
+            Self::Present => "present",
+            Self::Gone => "gone",
+        }
+    }
+}
+
+/// found parameters of the previous scan per target
+#[derive(Debug, Default)]
+pub struct Baseline {
+    found_params: HashMap<String, Vec<FoundParameter>>,
+}
+
+impl Baseline {
+    /// reads the json output of the previous scan
+    pub fn read(filename: &str) -> Result<Self, Box<dyn Error>> {
+        let outputs: Vec<RunnerOutput> = match serde_json::from_str(&fs::read_to_string(filename)?) {
+            Ok(val) => val,
+            Err(err) => Err(format!("Unable to parse the baseline file: {}", err))?,
+        };
+
+        Ok(Self::from_outputs(outputs))
+    }
+
+    pub fn from_outputs(outputs: Vec<RunnerOutput>) -> Self {
+        let mut found_params: HashMap<String, Vec<FoundParameter>> = HashMap::new();
+
+        for output in outputs {
+            found_params
+                .entry(Self::key(&output.method, &output.url, output.target_hash))
+                .or_default()
+                .extend(output.found_params);
+        }
+
+        Self { found_params }
+    }
+
+    /// targets are identified by the method, the url without the injection point and RequestDefaults::target_hash()
+    fn key(method: &str, url: &str, target_hash: u64) -> String {
+        format!("{} {} {:016x}", method, url, target_hash)
+    }
+
+    /// found parameters of the same target within the previous scan
+    fn previous(&self, method: &str, url: &str, target_hash: u64) -> Option<&Vec<FoundParameter>> {
+        self.found_params.get(&Self::key(method, url, target_hash))
+    }
+
+    /// whether the parameter was found within the previous scan of the target
+    pub fn status(&self, method: &str, url: &str, target_hash: u64, name: &str) -> BaselineStatus {
+        match self.previous(method, url, target_hash) {
+            Some(previous) if previous.contains_name(name) => BaselineStatus::Present,
+            _ => BaselineStatus::New,
+        }
+    }
+
+    /// marks found parameters as new or present and adds the gone ones to output.gone_params.
+    /// In case only_new is true -- leaves only new parameters
+    pub fn compare(&self, output: &mut RunnerOutput, only_new: bool) {
+        let empty = Vec::new();
+        let previous = self
+            .previous(&output.method, &output.url, output.target_hash)
+            .unwrap_or(&empty);
+
+        for found_param in output.found_params.iter_mut() {
+            found_param.baseline = if previous.contains_name(&found_param.name) {
+                Some(BaselineStatus::Present)
+            } else {
+                Some(BaselineStatus::New)
+            };
+        }
+
+        if only_new {
+            output
+                .found_params
+                .retain(|x| x.baseline == Some(BaselineStatus::New));
+            return;
+        }
+
+        output.gone_params = previous
+            .iter()
+            .filter(|x| !output.found_params.contains_name(&x.name))
+            .map(|x| FoundParameter {
+                baseline: Some(BaselineStatus::Gone),
+                saved_to: None,
+                ..x.clone()
+            })
+            .collect();
+    }
+}
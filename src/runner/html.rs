@@ -0,0 +1,151 @@
+use super::{
+    baseline::BaselineStatus,
+    output::RunnerOutput,
+    utils::{FoundParameter, ReasonKind},
+};
+
+/// max length of a single diff within the report
+const MAX_DIFF_LEN: usize = 200;
+
+/// (reason kind, class name, description)
+const REASONS: [(ReasonKind, &str, &str); 4] = [
+    (ReasonKind::Code, "code", "status code change"),
+    (ReasonKind::Text, "text", "body change"),
+    (ReasonKind::Reflected, "reflected", "reflection"),
+    (ReasonKind::NotReflected, "not-reflected", "reflection change"),
+];
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em;color:#222}
+table{border-collapse:collapse;margin-bottom:1.5em}
+th,td{border:1px solid #ccc;padding:4px 8px;text-align:left;vertical-align:top}
+th{background:#f0f0f0}
+code{white-space:pre-wrap;word-break:break-all}
+.code{color:#b35900}.text{color:#a0a000}.reflected{color:#0060c0}.not-reflected{color:#008b8b}
+.new{color:#008000;font-weight:bold}tr.gone td{color:#888;text-decoration:line-through}
+#filter label{margin-right:1em}";
+
+/// hides the rows of unchecked reason kinds
+const SCRIPT: &str = "function filter(){
+var kinds=[].slice.call(document.querySelectorAll('#filter input:checked')).map(function(x){return x.value});
+document.querySelectorAll('tr.param').forEach(function(row){
+row.style.display=kinds.indexOf(row.dataset.reason)==-1?'none':''});}";
+
+/// converts outputs to a self contained html page
+pub fn html(outputs: &[RunnerOutput]) -> String {
+    let mut page = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>x8 report</title>\n\
+        <style>\n{}\n</style>\n<script>\n{}\n</script>\n</head>\n<body>\n<h1>x8 report</h1>\n",
+        STYLE, SCRIPT
+    );
+
+    page += "<div id=\"filter\">Show: ";
+    for (_, class, description) in REASONS.iter() {
+        page += &format!(
+            "<label class=\"{0}\"><input type=\"checkbox\" value=\"{0}\" checked onchange=\"filter()\">{1}</label>",
+            class, description
+        );
+    }
+    page += "</div>\n";
+
+    page += "<h2>Targets</h2>\n<table>\n\
+        <tr><th>#</th><th>Method</th><th>Url</th><th>Injection place</th><th>Status</th><th>Size</th><th>Found</th><th>Gone</th></tr>\n";
+    for (i, output) in outputs.iter().enumerate() {
+        page += &format!(
+            "<tr><td>{}</td><td>{}</td><td><a href=\"#target-{}\">{}</a></td><td>{:?}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            i + 1,
+            escape(&output.method),
+            i + 1,
+            escape(&output.url),
+            output.injection_place,
+            output.status,
+            output.size,
+            output.found_params.len(),
+            output.gone_params.len()
+        );
+    }
+    page += "</table>\n";
+
+    for (i, output) in outputs.iter().enumerate() {
+        page += &target(i + 1, output);
+    }
+
+    page += "</body>\n</html>\n";
+    page
+}
+
+fn target(id: usize, output: &RunnerOutput) -> String {
+    let mut section = format!(
+        "<h2 id=\"target-{}\">{} {}</h2>\n",
+        id,
+        escape(&output.method),
+        escape(&output.url)
+    );
+
+    if output.found_params.is_empty() && output.gone_params.is_empty() {
+        return section + "<p>No parameters found</p>\n";
+    }
+
+    section += "<table>\n\
+        <tr><th>Parameter</th><th>Reason</th><th>Status</th><th>Size</th><th>Diffs</th><th>Saved</th><th>Baseline</th></tr>\n";
+    // parameters gone since the baseline are crossed out
+    for parameter in output.found_params.iter().chain(output.gone_params.iter()) {
+        section += &row(output, parameter);
+    }
+    section += "</table>\n";
+
+    section
+}
+
+fn row(output: &RunnerOutput, parameter: &FoundParameter) -> String {
+    let (_, class, description) = REASONS.iter().find(|x| x.0 == parameter.reason_kind).unwrap();
+
+    let name = match &parameter.value {
+        Some(value) => format!("{}={}", parameter.name, value),
+        None => parameter.name.clone(),
+    };
+
+    let diffs = parameter
+        .diffs
+        .split('|')
+        .filter(|x| !x.is_empty())
+        .map(|x| format!("<code>{}</code>", escape(&x.chars().take(MAX_DIFF_LEN).collect::<String>())))
+        .collect::<Vec<String>>()
+        .join("<br>");
+
+    let saved = match &parameter.saved_to {
+        Some(filename) => format!("<a href=\"{0}\">{0}</a>", escape(filename)),
+        None => String::new(),
+    };
+
+    let (row_class, baseline) = match parameter.baseline {
+        Some(BaselineStatus::Gone) => ("param gone", "gone"),
+        Some(baseline) => ("param", baseline.as_str()),
+        None => ("param", ""),
+    };
+
+    format!(
+        "<tr class=\"{}\" data-reason=\"{}\"><td><code>{}</code></td><td class=\"{}\">{}</td>\
+        <td>{} &rarr; {}</td><td>{} &rarr; {}</td><td>{}</td><td>{}</td><td class=\"{}\">{}</td></tr>\n",
+        row_class,
+        class,
+        escape(&name),
+        class,
+        description,
+        output.status,
+        parameter.status,
+        output.size,
+        parameter.size,
+        diffs,
+        saved,
+        baseline,
+        baseline
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
@@ -1,16 +1,38 @@
-use std::{cmp, collections::HashMap, error::Error, sync::Arc};
+use std::{
+    cmp,
+    collections::HashMap,
+    error::Error,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
 use async_recursion::async_recursion;
 use futures::stream::StreamExt;
 use parking_lot::Mutex;
 
 use crate::{
-    network::request::Request,
-    runner::utils::{FoundParameter, ReasonKind}, utils::progress_style_check_requests,
+    network::{request::Request, response::Response, utils::save_request},
+    runner::{
+        baseline::BaselineStatus,
+        output::output_url,
+        utils::{FoundParameter, ReasonKind, Slowdown},
+    },
+    scanner::observer::Finding,
 };
 
 use super::runner::Runner;
 
+/// how many times a chunk is resent in case the host limits the rate of requests
+const MAX_RATE_LIMIT_RETRIES: u32 = 8;
+
+/// the pause in case there's no Retry-After header. Doubles with every retry of the same chunk
+const DEFAULT_PAUSE: Duration = Duration::from_secs(5);
+
+const MAX_PAUSE: Duration = Duration::from_secs(300);
+
 /// impl logic for checking parameters
 impl<'a> Runner<'a> {
     /// just splits params into two parts and runs check_parameters_recursion for every part
@@ -20,25 +42,30 @@ impl<'a> Runner<'a> {
         shared_green_lines: Arc<Mutex<&'a mut HashMap<String, usize>>>,
         shared_found_params: Arc<Mutex<&'a mut Vec<FoundParameter>>>,
         mut params: Vec<String>,
-    ) -> Result<(), Box<dyn Error>> {
+    ) -> Result<bool, Box<dyn Error>> {
         let second_params_part = params.split_off(params.len() / 2);
 
-        self.check_parameters_recursion(
-            Arc::clone(&shared_diffs),
-            Arc::clone(&shared_green_lines),
-            Arc::clone(&shared_found_params),
-            params,
-        )
-        .await?;
-        self.check_parameters_recursion(
-            shared_diffs,
-            shared_green_lines,
-            shared_found_params,
-            second_params_part,
-        )
-        .await
+        let first_checked = self
+            .check_parameters_recursion(
+                Arc::clone(&shared_diffs),
+                Arc::clone(&shared_green_lines),
+                Arc::clone(&shared_found_params),
+                params,
+            )
+            .await?;
+        let second_checked = self
+            .check_parameters_recursion(
+                shared_diffs,
+                shared_green_lines,
+                shared_found_params,
+                second_params_part,
+            )
+            .await?;
+
+        Ok(first_checked && second_checked)
     }
 
+    /// returns false in case some requests of the chunk failed and its parameters were added to unchecked_params
     #[async_recursion(?Send)]
     async fn check_parameters_recursion(
         &self,
@@ -46,20 +73,39 @@ impl<'a> Runner<'a> {
         shared_green_lines: Arc<Mutex<&'a mut HashMap<String, usize>>>,
         shared_found_params: Arc<Mutex<&'a mut Vec<FoundParameter>>>,
         mut params: Vec<String>,
-    ) -> Result<(), Box<dyn Error>> {
+    ) -> Result<bool, Box<dyn Error>> {
         let request = Request::new(&self.request_defaults, params.clone());
-        let mut response = match request.clone().wrapped_send().await {
-            Ok(val) => val,
-            Err(_) => match Request::new_random(&self.request_defaults, params.len())
-                .send()
-                .await
-            {
-                //we don't return the actual response because it was a random request without original parameters
-                //instead we return an empty response from the original request
-                Ok(_) => request.empty_response(),
-                //looks like either server or network is down
-                Err(err) => Err(format!("Unable to reach server ({})", err))?,
-            },
+
+        // rate limit responses are never compared, the chunk is resent after the pause instead
+        let mut retries = 0;
+        let mut response = loop {
+            let response = match request.clone().wrapped_send().await {
+                Ok(val) => val,
+                Err(err) => {
+                    //looks like either server or network is down
+                    if let Err(err) = Request::new_random(&self.request_defaults, params.len()).send().await {
+                        Err(format!("Unable to reach server ({})", err))?
+                    }
+
+                    // the chunk failed even after all the retries, so it's reported instead of being lost
+                    self.warning(format!("unable to check {} parameters ({})", params.len(), err));
+                    self.unchecked_params.lock().append(&mut params);
+
+                    return Ok(false);
+                }
+            };
+
+            match response.rate_limit_reason(&self.initial_response, &self.config.rate_limit_signatures) {
+                Some(_) if retries == MAX_RATE_LIMIT_RETRIES => Err(format!(
+                    "{} The server keeps limiting the rate of requests",
+                    self.request_defaults.url()
+                ))?,
+                Some(reason) => {
+                    self.throttle(reason, response.retry_after(), retries);
+                    retries += 1;
+                }
+                None => break response,
+            }
         };
 
         if self.stable.reflections {
@@ -78,13 +124,14 @@ impl<'a> Runner<'a> {
                         kind = ReasonKind::NotReflected;
                     }
 
-                    found_params.push(FoundParameter::new(
+                    let mut found_param = FoundParameter::new(
                         reflected_parameter,
-                        &vec![],
+                        &[],
                         response.code,
                         response.text.len(),
-                        kind.clone(),
-                    ));
+                        kind,
+                    );
+                    found_params.push(found_param.clone());
                     drop(found_params);
 
                     // remove found parameter from the list
@@ -95,15 +142,7 @@ impl<'a> Runner<'a> {
                             .unwrap(),
                     );
 
-                    response.write_and_save(
-                        self.id,
-                        self.config,
-                        &self.initial_response,
-                        kind,
-                        reflected_parameter,
-                        None,
-                        self.progress_bar,
-                    )?;
+                    self.report(&response, &mut found_param, None)?;
                 }
             }
 
@@ -119,7 +158,7 @@ impl<'a> Runner<'a> {
             }
 
             if self.config.reflected_only {
-                return Ok(());
+                return Ok(true);
             }
         }
 
@@ -127,61 +166,63 @@ impl<'a> Runner<'a> {
             // increases the specific response code counter
             // helps to notice whether the page's completely changed
             // like, for example, when the IP got banned by the server
-            {
+            let code_count = {
                 let mut green_lines = shared_green_lines.lock();
-                match green_lines.get(&response.code.to_string()) {
-                    Some(val) => {
-                        let n_val = *val;
-                        green_lines.insert(response.code.to_string(), n_val + 1);
-                        if n_val > 50 {
-                            drop(green_lines);
-
-                            let check_response =
-                                Request::new_random(&self.request_defaults, params.len())
-                                    .wrapped_send()
-                                    .await
-                                    .unwrap_or_default();
-
-                            if check_response.code != self.initial_response.code {
-                                return Err(format!(
-                                    "{} The page became unstable (code)",
-                                    self.request_defaults.url()
-                                ))?;
-                            } else {
-                                let mut green_lines = shared_green_lines.lock();
-                                green_lines.insert(response.code.to_string(), 0);
-                            }
-                        }
-                    }
-                    _ => {
-                        green_lines.insert(response.code.to_string(), 0);
-                    }
+                let code_count = green_lines.get(&response.code.to_string()).copied();
+                green_lines.insert(response.code.to_string(), code_count.map_or(0, |x| x + 1));
+                code_count
+            };
+
+            if code_count.is_some_and(|x| x > 50) {
+                let check_response = Request::new_random(&self.request_defaults, params.len())
+                    .wrapped_send()
+                    .await
+                    .unwrap_or_default();
+
+                let rate_limit_reason = check_response
+                    .rate_limit_reason(&self.initial_response, &self.config.rate_limit_signatures);
+
+                if let Some(reason) = rate_limit_reason {
+                    self.throttle(reason, check_response.retry_after(), 0);
+
+                    shared_green_lines.lock().insert(response.code.to_string(), 0);
+
+                    // the response was probably affected by the rate limit as well
+                    // so the chunk is sent again after the pause instead of being compared
+                    return self
+                        .check_parameters_recursion(
+                            shared_diffs,
+                            shared_green_lines,
+                            shared_found_params,
+                            params,
+                        )
+                        .await;
+                } else if check_response.code != self.initial_response.code {
+                    return Err(format!(
+                        "{} The page became unstable (code)",
+                        self.request_defaults.url()
+                    ))?;
+                } else {
+                    shared_green_lines.lock().insert(response.code.to_string(), 0);
                 }
             }
 
             // there's only 1 parameter left that's changing the page's code
             if params.len() == 1 {
-                response.write_and_save(
-                    self.id,
-                    self.config,
-                    &self.initial_response,
-                    ReasonKind::Code,
+                let mut found_param = FoundParameter::new(
                     &params[0],
-                    None,
-                    self.progress_bar,
-                )?;
-
-                let mut found_params = shared_found_params.lock();
-                found_params.push(FoundParameter::new(
-                    &params[0],
-                    &vec![format!(
+                    &[format!(
                         "{} -> {}",
                         &self.initial_response.code, response.code
                     )],
                     response.code,
                     response.text.len(),
                     ReasonKind::Code,
-                ));
+                );
+
+                self.report(&response, &mut found_param, None)?;
+
+                shared_found_params.lock().push(found_param);
             // there's more than 1 parameter left - split the list and repeat
             } else {
                 return self
@@ -205,14 +246,14 @@ impl<'a> Runner<'a> {
                 if self.config.strict {
                     let found_params = shared_found_params.lock();
                     if found_params.iter().any(|x| x.diffs == new_diffs.join("|")) {
-                        return Ok(());
+                        return Ok(true);
                     }
                 }
 
                 // just request the page with random parameters and store it's diffs
                 // maybe I am overcheking this, but still to be sure..
-                let tmp_resp = Request::new_random(&self.request_defaults, params.len())
-                    .send()
+                let tmp_resp = self
+                    .send_with_throttling(Request::new_random(&self.request_defaults, params.len()))
                     .await?;
 
                 let (_, tmp_diffs) = {
@@ -226,103 +267,269 @@ impl<'a> Runner<'a> {
                 }
             }
 
-            let diffs = shared_diffs.lock();
-
-            // check whether the page still(after making a random request and storing it's diffs) has an unique diffs
-            for diff in new_diffs.iter() {
-                if !diffs.contains(diff) {
-                    let mut found_params = shared_found_params.lock();
+            // the locks are released before the chunk is split, so the other chunks aren't blocked
+            let is_unknown_parameter = {
+                let diffs = shared_diffs.lock();
+                let mut is_unknown_parameter = false;
+
+                // check whether the page still(after making a random request and storing it's diffs) has an unique diffs
+                for diff in new_diffs.iter() {
+                    if !diffs.contains(diff) {
+                        let mut found_params = shared_found_params.lock();
+
+                        // there's only one parameter left that changing the page
+                        if params.len() == 1 && !found_params.iter().any(|x| x.name == params[0]) {
+                            // repeating --strict checks. We need to do it twice because we're usually running in parallel
+                            // and some parameters may be found after the first check
+                            if self.config.strict && found_params.iter().any(|x| x.diffs == new_diffs.join("|")) {
+                                return Ok(true);
+                            }
 
-                    // there's only one parameter left that changing the page
-                    if params.len() == 1 && !found_params.iter().any(|x| x.name == params[0]) {
-                        // repeating --strict checks. We need to do it twice because we're usually running in parallel
-                        // and some parameters may be found after the first check
-                        if self.config.strict && found_params.iter().any(|x| x.diffs == new_diffs.join("|")) {
-                            return Ok(());
+                            let mut found_param = FoundParameter::new(
+                                &params[0],
+                                &new_diffs,
+                                response.code,
+                                response.text.len(),
+                                ReasonKind::Text,
+                            );
+
+                            self.report(&response, &mut found_param, Some(diff))?;
+
+                            found_params.push(found_param);
+                        // we don't know what parameter caused the difference in response yet
+                        // so we are repeating
+                        } else {
+                            is_unknown_parameter = true;
                         }
-
-                        response.write_and_save(
-                            self.id,
-                            self.config,
-                            &self.initial_response,
-                            ReasonKind::Text,
-                            &params[0],
-                            Some(diff),
-                            self.progress_bar,
-                        )?;
-
-                        found_params.push(FoundParameter::new(
-                            &params[0],
-                            &new_diffs,
-                            response.code,
-                            response.text.len(),
-                            ReasonKind::Text,
-                        ));
                         break;
-                    // we don't know what parameter caused the difference in response yet
-                    // so we are repeating
-                    } else {
-                        drop(diffs);
-                        drop(found_params);
-                        return self
-                            .repeat(
-                                shared_diffs,
-                                shared_green_lines,
-                                shared_found_params,
-                                params.clone(),
-                            )
-                            .await;
                     }
                 }
+
+                is_unknown_parameter
+            };
+
+            if is_unknown_parameter {
+                return self
+                    .repeat(
+                        shared_diffs,
+                        shared_green_lines,
+                        shared_found_params,
+                        params.clone(),
+                    )
+                    .await;
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// sends the request once again after the pause in case the host limits the rate of requests
+    pub(super) async fn send_with_throttling<'b>(
+        &'b self,
+        request: Request<'b>,
+    ) -> Result<Response<'b>, Box<dyn Error>> {
+        let mut retries = 0;
+
+        loop {
+            let response = request.clone().send().await?;
+
+            match response.rate_limit_reason(&self.initial_response, &self.config.rate_limit_signatures) {
+                Some(_) if retries == MAX_RATE_LIMIT_RETRIES => Err(format!(
+                    "{} The server keeps limiting the rate of requests",
+                    self.request_defaults.url()
+                ))?,
+                Some(reason) => {
+                    self.throttle(reason, response.retry_after(), retries);
+                    retries += 1;
+                }
+                None => return Ok(response),
+            }
+        }
+    }
+
+    /// pauses the host. Every runner with the same host waits as well because the rate limiter is shared
+    fn throttle(&self, reason: String, retry_after: Option<Duration>, retries: u32) {
+        let pause = retry_after
+            .unwrap_or(DEFAULT_PAUSE * 2u32.pow(retries))
+            .min(MAX_PAUSE);
+
+        self.request_defaults
+            .rate_limiter
+            .pause(&self.request_defaults.host, pause);
+
+        self.info(
+            "throttle",
+            format!(
+                "the server limits the rate of requests ({}). Pausing {} for {}s",
+                reason,
+                self.request_defaults.host,
+                pause.as_secs()
+            ),
+        );
+
+        self.slowdowns.lock().push(Slowdown {
+            reason,
+            pause: pause.as_secs(),
+        });
+    }
+
+    /// saves the request and response in case --save-responses is used and passes the parameter to the observer.
+    /// In case --only-new is used -- parameters from the baseline aren't passed
+    fn report(
+        &self,
+        response: &Response,
+        parameter: &mut FoundParameter,
+        diff: Option<&str>,
+    ) -> Result<(), Box<dyn Error>> {
+        if let Some(baseline) = self.baseline {
+            let status = baseline.status(
+                &self.request_defaults.method,
+                &output_url(&self.request_defaults.url_without_default_port(), self.request_defaults.injection_place),
+                self.request_defaults.target_hash(),
+                &parameter.name,
+            );
+            parameter.baseline = Some(status);
+
+            if self.config.only_new && status == BaselineStatus::Present {
+                return Ok(());
             }
         }
 
+        parameter.saved_to = if !self.config.save_responses.is_empty() {
+            let key = match &parameter.value {
+                Some(value) => format!("{}={}", parameter.name, value),
+                None => parameter.name.clone(),
+            };
+            Some(save_request(self.config, response, &key)?)
+        } else {
+            None
+        };
+
+        self.observer.on_parameter_found(
+            &self.target,
+            &Finding {
+                parameter,
+                initial_status: self.initial_response.code,
+                initial_size: self.initial_response.text.len(),
+                diff,
+            },
+        );
+
         Ok(())
     }
 
     /// check parameters in a loop chunk by chunk
+    /// save_progress is true only for the main list of parameters, so the processed chunks can be skipped with --resume
     pub async fn check_parameters(
         &self,
-        params: &Vec<String>,
+        params: &[String],
+        save_progress: bool,
     ) -> Result<(Vec<String>, Vec<FoundParameter>), Box<dyn Error>> {
         let max = cmp::min(self.max, params.len());
 
         // the amount of requests needed for process all the parameters
-        let all = params.len() / max;
+        let all = params.len().div_ceil(max);
+        let done = AtomicUsize::new(0);
+
+        self.observer.on_chunk_done(&self.target, 0, all);
+
+        let state = if save_progress { self.state } else { None };
 
-        // change and reset the progress bar
-        self.prepare_progress_bar(progress_style_check_requests(self.config), all + 1);
+        // chunks processed before the scan was interrupted
+        let (processed_chunks, mut found_params) = match state
+            .and_then(|x| x.runner_state(&self.request_defaults, max, params))
+        {
+            Some(runner_state) => {
+                self.info("resume", format!("skipping {} processed chunks", runner_state.chunks.len()));
+                done.store(runner_state.chunks.len(), Ordering::Relaxed);
+                self.observer.on_chunk_done(&self.target, runner_state.chunks.len(), all);
+
+                (runner_state.chunks, runner_state.found_params)
+            }
+            None => (Vec::new(), Vec::new()),
+        };
 
         // wrap the variables to share them between futures
         let mut diffs = self.diffs.clone();
         let mut green_lines = HashMap::new();
-        let mut found_params = Vec::new();
 
         let shared_diffs = Arc::new(Mutex::new(&mut diffs));
         let shared_green_lines = Arc::new(Mutex::new(&mut green_lines));
         let shared_found_params = Arc::new(Mutex::new(&mut found_params));
 
-        let _futures_data = futures::stream::iter(params.chunks(max).map(|chunk| {
+        let chunks = params
+            .chunks(max)
+            .enumerate()
+            .filter(|(i, _)| !processed_chunks.contains(i));
+
+        let results = futures::stream::iter(chunks.map(|(i, chunk)| {
             let shared_diffs = Arc::clone(&shared_diffs);
             let shared_green_lines = Arc::clone(&shared_green_lines);
             let shared_found_params = Arc::clone(&shared_found_params);
+            let done = &done;
 
             async move {
-                self.progress_bar.inc(1);
-
-                self.check_parameters_recursion(
-                    shared_diffs,
-                    shared_green_lines,
-                    shared_found_params,
-                    chunk.to_vec(),
-                )
-                .await
+                let result = self
+                    .check_parameters_recursion(
+                        shared_diffs,
+                        shared_green_lines,
+                        Arc::clone(&shared_found_params),
+                        chunk.to_vec(),
+                    )
+                    .await;
+
+                let is_checked = match result {
+                    Ok(val) => val,
+                    // the chunk isn't lost -- its parameters are reported as unchecked
+                    Err(err) => {
+                        self.warning(format!("unable to check {} parameters ({})", chunk.len(), err));
+
+                        let found_params = shared_found_params.lock();
+                        self.unchecked_params.lock().extend(
+                            chunk
+                                .iter()
+                                .filter(|x| !found_params.iter().any(|found| found.name == **x))
+                                .cloned(),
+                        );
+
+                        false
+                    }
+                };
+
+                // the chunk is saved only after it was fully processed
+                // chunks with failed requests are checked again after --resume
+                let saved = match state.filter(|_| is_checked) {
+                    Some(state) => {
+                        // other chunks can still be running, so only this chunk's parameters are saved
+                        let chunk_params: Vec<FoundParameter> = shared_found_params
+                            .lock()
+                            .iter()
+                            .filter(|x| {
+                                chunk.contains(&x.name)
+                                    || x.value.as_ref().is_some_and(|value| chunk.contains(&format!("{}={}", x.name, value)))
+                            })
+                            .cloned()
+                            .collect();
+
+                        state.save_chunk(&self.request_defaults, max, params, i, &chunk_params)
+                    }
+                    None => Ok(()),
+                };
+
+                self.observer.on_chunk_done(&self.target, done.fetch_add(1, Ordering::Relaxed) + 1, all);
+
+                saved
             }
         }))
         .buffer_unordered(self.config.concurrency)
         .collect::<Vec<Result<(), Box<dyn Error>>>>()
         .await;
 
+        // chunks can't fail anymore, so only the errors of the state file are left
+        for result in results {
+            result?;
+        }
+
         Ok((diffs, found_params))
     }
 }
@@ -1,4 +1,14 @@
+pub mod baseline;
+pub mod html;
 pub mod logic;
 pub mod output;
+#[allow(clippy::module_inception)]
 pub mod runner;
+pub mod sarif;
+pub mod scripts;
+pub mod state;
+pub mod table;
 pub mod utils;
+
+#[allow(clippy::module_inception)]
+mod tests;
@@ -1,4 +1,4 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use colored::Colorize;
 
 use crate::{
@@ -10,9 +10,15 @@ use crate::{
     },
 };
 
-use super::utils::FoundParameter;
+use super::{
+    baseline::BaselineStatus,
+    html::html,
+    sarif::sarif,
+    table,
+    utils::{FoundParameter, Slowdown},
+};
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct RunnerOutput {
     /// request's method
     pub method: String,
@@ -20,6 +26,10 @@ pub struct RunnerOutput {
     /// request url without injection point
     pub url: String,
 
+    /// see RequestDefaults::target_hash()
+    #[serde(default)]
+    pub target_hash: u64,
+
     /// initial response code
     pub status: u16,
 
@@ -28,18 +38,79 @@ pub struct RunnerOutput {
 
     pub found_params: Vec<FoundParameter>,
 
+    /// parameters that were found only within the previous scan (--baseline)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub gone_params: Vec<FoundParameter>,
+
     pub injection_place: InjectionPlace,
 
+    /// pauses caused by the host limiting the rate of requests
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub slowdowns: Vec<Slowdown>,
+
+    /// requests that failed even after all the retries
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub failed_requests: usize,
+
+    /// parameters that weren't checked due to failed requests
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unchecked_params: Vec<String>,
 
     /// prepared query with found parameters
-    #[serde(skip_serializing)]
+    #[serde(skip)]
     pub query: String,
 
     /// prepared request with found parameters
-    #[serde(skip_serializing)]
+    #[serde(skip)]
     pub request: String,
+
+    /// prepared curl commands, one per found parameter
+    #[serde(skip)]
+    pub curl: String,
 }
 
+fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+/// a line of the jsonl output. Written as soon as it happens
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum OutputEvent<'a> {
+    /// a parameter was found. It can be filtered out later, so the summary contains the final list
+    Finding {
+        method: &'a str,
+        url: String,
+        injection_place: InjectionPlace,
+        #[serde(flatten)]
+        parameter: &'a FoundParameter,
+        initial_status: u16,
+        initial_size: usize,
+    },
+
+    /// the url:method pair was checked
+    Summary(&'a RunnerOutput),
+
+    /// the url:method pair wasn't checked
+    Error {
+        method: &'a str,
+        url: &'a str,
+        error: &'a str,
+    },
+}
+
+/// removes the injection point in case it's within the url
+pub fn output_url(url: &str, injection_place: InjectionPlace) -> String {
+    if injection_place == InjectionPlace::Path {
+        url.replace("?%s", "").replace("&%s", "")
+    } else {
+        url.to_string()
+    }
+}
+
+/// output formats that are written once the whole scan is finished
+pub const SCAN_OUTPUT_FORMATS: [&str; 3] = ["json", "sarif", "html"];
+
 pub trait ParseOutputs {
     fn parse_output(&self, config: &Config) -> String;
 }
@@ -52,22 +123,23 @@ impl RunnerOutput {
     ) -> Self {
         Self {
             method: request_defaults.method.clone(),
-            //remove injection point in case the injection point within url
-            url: if request_defaults.injection_place == InjectionPlace::Path {
-                request_defaults.url_without_default_port().replace("?%s", "").replace("&%s", "")
-            } else {
-                request_defaults.url_without_default_port()
-            },
+            url: output_url(&request_defaults.url_without_default_port(), request_defaults.injection_place),
+            target_hash: request_defaults.target_hash(),
             status: initial_response.code,
             size: initial_response.text.len(),
             found_params,
+            gone_params: Vec::new(),
             injection_place: request_defaults.injection_place,
+            slowdowns: Vec::new(),
+            failed_requests: 0,
+            unchecked_params: Vec::new(),
             query: String::new(),
             request: String::new(),
+            curl: String::new(),
         }
     }
 
-    /// fills self.request and self.query if they're needed for output
+    /// fills self.request, self.query or self.curl if they're needed for output
     pub fn prepare(&mut self, config: &Config, request_defaults: &RequestDefaults) {
         if config.output_format == "url" || config.output_format == "request" {
             let mut request = Request::new(
@@ -78,12 +150,9 @@ impl RunnerOutput {
                         //in case a parameter has a non standart value (like 'true')
                         //it should be treated differently (=true) should be added
                         //otherwise that parameter will have random value
-                        |x| {
-                            if x.value.is_none() {
-                                x.name.to_owned()
-                            } else {
-                                format!("{}={}", x.name, x.value.as_ref().unwrap())
-                            }
+                        |x| match &x.value {
+                            Some(value) => format!("{}={}", x.name, value),
+                            None => x.name.to_owned(),
                         },
                     )
                     .collect(),
@@ -96,6 +165,23 @@ impl RunnerOutput {
             } else {
                 self.request = request.print();
             }
+        } else if config.output_format == "curl" {
+            // a separate command per parameter to reproduce every finding on its own
+            self.curl = self
+                .found_params
+                .iter()
+                .map(|x| {
+                    let parameter = match &x.value {
+                        Some(value) => format!("{}={}", x.name, value),
+                        None => x.name.to_owned(),
+                    };
+
+                    let mut request = Request::new(request_defaults, vec![parameter]);
+                    request.prepare();
+                    request.curl()
+                })
+                .collect::<Vec<String>>()
+                .join("\n");
         }
     }
 
@@ -121,17 +207,39 @@ impl RunnerOutput {
 
             "request" => self.request.clone(),
 
+            "curl" => self.curl.clone(),
+
+            "csv" | "markdown" => table::rows(self, &config.output_format),
+
             _ => {
-                format!(
+                let mut line = format!(
                     "{} {} % {}",
                     &self.method.blue(),
                     &self.url,
                     self.found_params
                         .iter()
-                        .map(|x| x.get_colored())
+                        .map(|x| if x.baseline == Some(BaselineStatus::New) {
+                            format!("{} {}", x.get_colored(), "(new)".green())
+                        } else {
+                            x.get_colored()
+                        })
                         .collect::<Vec<String>>()
                         .join(", ")
-                )
+                );
+
+                if !self.gone_params.is_empty() {
+                    line += &format!(
+                        " % {} {}",
+                        "gone:".red(),
+                        self.gone_params
+                            .iter()
+                            .map(|x| x.name.as_str())
+                            .collect::<Vec<&str>>()
+                            .join(", ")
+                    );
+                }
+
+                line
             }
         }
     }
@@ -142,6 +250,10 @@ impl ParseOutputs for Vec<RunnerOutput> {
         // print an array of json objects instead of just new line separeted new objects
         if config.output_format.as_str() == "json" {
             serde_json::to_string(&self).unwrap()
+        } else if config.output_format.as_str() == "sarif" {
+            sarif(self)
+        } else if config.output_format.as_str() == "html" {
+            html(self)
         // otherwise calls .parse on every RunnerOutput
         } else {
             self.iter()
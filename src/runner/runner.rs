@@ -1,27 +1,29 @@
-use std::{error::Error, io::{self, Write}};
+use std::{collections::HashMap, error::Error, fmt::Display, sync::atomic::Ordering};
 
-use colored::Colorize;
-use indicatif::{ProgressBar, ProgressStyle};
+use parking_lot::Mutex;
 
 use crate::{
     config::structs::Config,
     network::{
         request::{Request, RequestDefaults},
         response::Response,
-        utils::{create_client, InjectionPlace},
+        utils::{create_client, Headers, InjectionPlace},
     },
-    utils::{self, color_id, random_line, progress_style_learn_requests, is_id_important},
-    DEFAULT_PROGRESS_URL_MAX_LEN, MAX_PAGE_SIZE,
+    scanner::observer::{InitialResponse, LearnStats, ScanObserver, TargetInfo},
+    utils::random_line,
+    MAX_PAGE_SIZE,
 };
 
 use super::{
+    baseline::Baseline,
     output::RunnerOutput,
-    utils::{fold_url, replay, verify, FoundParameter, Parameters, Stable},
+    state::StateFile,
+    utils::{replay, verify, FoundParameter, Parameters, Slowdown, Stable},
 };
 
 pub struct Runner<'a> {
-    /// unique id of the runner to distinguish output between different urls
-    pub id: usize,
+    /// the url:method pair passed to the observer with every event
+    pub target: TargetInfo,
 
     pub config: &'a Config,
 
@@ -31,6 +33,9 @@ pub struct Runner<'a> {
     /// parameters found by scraping words from the page
     pub possible_params: Vec<String>,
 
+    /// parameter -> the script it was mined from (--mine-scripts)
+    pub possible_params_sources: HashMap<String, String>,
+
     /// the max amount of parameters to send per request
     pub max: usize,
 
@@ -43,8 +48,20 @@ pub struct Runner<'a> {
     /// page's diffs for the current url|method pair
     pub diffs: Vec<String>,
 
-    /// progress bar object to print progress bar & found parameters
-    pub progress_bar: &'a ProgressBar,
+    /// receives progress, found parameters and messages
+    pub observer: &'a dyn ScanObserver,
+
+    /// stores processed chunks in case --state or --resume is used
+    pub state: Option<&'a StateFile>,
+
+    /// found parameters of the previous scan in case --baseline is used
+    pub baseline: Option<&'a Baseline>,
+
+    /// pauses caused by the host limiting the rate of requests
+    pub slowdowns: Mutex<Vec<Slowdown>>,
+
+    /// parameters from chunks that failed even after all the retries
+    pub unchecked_params: Mutex<Vec<String>>,
 }
 
 impl<'a> Runner<'a> {
@@ -52,8 +69,10 @@ impl<'a> Runner<'a> {
     pub async fn new(
         config: &'a Config,
         request_defaults: &'a mut RequestDefaults,
-        progress_bar: &'a ProgressBar,
-        id: usize,
+        observer: &'a dyn ScanObserver,
+        target: TargetInfo,
+        state: Option<&'a StateFile>,
+        baseline: Option<&'a Baseline>,
     ) -> Result<Runner<'a>, Box<dyn Error>> {
         // make first request and collect some information like code, reflections, possible parameters
         // we are making another request defaults because the original one will be changed right after
@@ -96,51 +115,65 @@ impl<'a> Runner<'a> {
             config,
             request_defaults: request_defaults.clone(),
             possible_params,
+            possible_params_sources: HashMap::new(),
             max: 0, //to be filled later, in stability-checker()
             stable: Default::default(),
             initial_response,
             diffs: Vec::new(),
-            progress_bar,
-            id,
+            observer,
+            target,
+            state,
+            baseline,
+            slowdowns: Mutex::new(Vec::new()),
+            unchecked_params: Mutex::new(Vec::new()),
         })
     }
 
     /// actually runs the runner
     pub async fn run(mut self, params: &mut Vec<String>) -> Result<RunnerOutput, Box<dyn Error>> {
-        self.write_banner_url();
+        self.observer.on_target_start(
+            &self.target,
+            &InitialResponse {
+                status: self.initial_response.code,
+                size: self.initial_response.text.len(),
+                reflections: self.request_defaults.amount_of_reflections,
+            },
+        );
 
         // makes a few request to check page's behavior
         self.stability_checker().await?;
 
+        self.observer.on_learn_complete(
+            &self.target,
+            &LearnStats {
+                max: self.max,
+                stable_body: self.stable.body,
+                stable_reflections: self.stable.reflections,
+                diffs: self.diffs.len(),
+            },
+        );
+
         if self.config.max.is_none() {
-            utils::info(
-                self.config,
-                self.id,
-                self.progress_bar,
-                "info",
-                format!("Amount of parameters per request - {}", self.max),
-            );
+            self.info("info", format!("Amount of parameters per request - {}", self.max));
+        }
+
+        if self.config.mine_scripts && self.request_defaults.injection_place != InjectionPlace::Headers {
+            self.mine_scripts().await;
         }
 
         // add only unique possible params to the vec of all params (the tool works properly only with unique parameters)
         // less efficient than making it within the sorted vec but I want to preserve the order
         for param in self.possible_params.iter() {
-            if !params.contains(param) {
+            if !params.contains(param) && !self.request_defaults.parameters.contains_key(param) {
                 params.push(param.to_owned());
             }
         }
 
         // try to find existing parameters from the list
         let (diffs, mut found_params) = if !params.is_empty() {
-            self.check_parameters(params).await?
+            self.check_parameters(params, true).await?
         } else {
-            utils::info(
-                self.config,
-                self.id,
-                self.progress_bar,
-                "info",
-                "No parameters were provided",
-            );
+            self.info("info", "No parameters were provided");
             (Vec::new(), Vec::new())
         };
 
@@ -149,6 +182,12 @@ impl<'a> Runner<'a> {
         // remove duplicates
         let mut found_params = found_params.process(self.request_defaults.injection_place);
 
+        for param in found_params.iter() {
+            if let Some(source) = self.possible_params_sources.get(&param.name) {
+                self.info("info", format!("{} was mined from {}", param.name, source));
+            }
+        }
+
         // verify found parameters
         if self.config.verify {
             found_params = if let Ok(filtered_params) = verify(
@@ -162,13 +201,7 @@ impl<'a> Runner<'a> {
             {
                 filtered_params
             } else {
-                utils::info(
-                    self.config,
-                    self.id,
-                    self.progress_bar,
-                    "~",
-                    "was unable to verify found parameters",
-                );
+                self.warning("was unable to verify found parameters");
                 found_params
             };
         }
@@ -176,45 +209,51 @@ impl<'a> Runner<'a> {
         // replay request with found parameters via another proxy
         if !self.config.replay_proxy.is_empty() {
 
-            let client = match create_client(self.config, true) {
+            let client = match create_client(self.config, self.request_defaults.http_version, true) {
                 Ok(val) => Some(val),
                 Err(err) => {
-                    utils::info(
-                        self.config,
-                        self.id,
-                        self.progress_bar,
-                        "~",
-                        err,
-                    );
+                    self.warning(err);
 
                     None
                 }
             };
 
-            if client.is_some() {
+            if let Some(client) = client {
                 if replay(
                     self.config,
                     &self.request_defaults,
-                    &client.unwrap(),
+                    &client,
                     &found_params,
                 ).await
                 .is_err() {
-                    utils::info(
-                        self.config,
-                        self.id,
-                        self.progress_bar,
-                        "~",
-                        "was unable to resend found parameters via another proxy",
-                    );
+                    self.warning("was unable to resend found parameters via another proxy");
                 }
             }
         }
 
-        Ok(RunnerOutput::new(
+        let mut runner_output = RunnerOutput::new(
             &self.request_defaults,
             &self.initial_response,
             found_params,
-        ))
+        );
+        runner_output.slowdowns = self.slowdowns.into_inner();
+        runner_output.failed_requests = self.request_defaults.failures.failed.load(Ordering::Relaxed);
+        runner_output.unchecked_params = self.unchecked_params.into_inner();
+
+        if runner_output.failed_requests != 0 {
+            // self is partially moved above
+            self.observer.on_warning(
+                &self.target,
+                &format!(
+                    "{} requests failed ({} retries were made), {} parameters weren't checked",
+                    runner_output.failed_requests,
+                    self.request_defaults.failures.retries.load(Ordering::Relaxed),
+                    runner_output.unchecked_params.len()
+                ),
+            );
+        }
+
+        Ok(runner_output)
     }
 
     /// check parameters with non random values
@@ -235,6 +274,7 @@ impl<'a> Runner<'a> {
                         .iter()
                         .map(|x| x.name.split('=').next().unwrap())
                         .any(|x| x == k)
+                        || self.request_defaults.parameters.contains_key(k)
                     {
                         continue;
                     }
@@ -248,7 +288,7 @@ impl<'a> Runner<'a> {
                     break;
                 }
 
-                found_params.append(&mut self.check_parameters(&params).await?.1);
+                found_params.append(&mut self.check_parameters(&params, false).await?.1);
                 params.clear();
             }
         }
@@ -296,18 +336,17 @@ impl<'a> Runner<'a> {
         };
         let mut diffs: Vec<String> = Vec::new();
 
-        // set up progress bar
-        self.prepare_progress_bar(progress_style_learn_requests(self.config), self.config.learn_requests_count);
+        self.observer.on_learn_progress(&self.target, 0, self.config.learn_requests_count);
 
-        for _ in 0..self.config.learn_requests_count {
+        for i in 0..self.config.learn_requests_count {
             // to increase stability
             tokio::time::sleep(tokio::time::Duration::from_millis(150)).await;
 
-            let response = Request::new_random(&self.request_defaults, self.max)
-                .send()
+            let response = self
+                .send_with_throttling(Request::new_random(&self.request_defaults, self.max))
                 .await?;
 
-            self.progress_bar.inc(1);
+            self.observer.on_learn_progress(&self.target, i + 1, self.config.learn_requests_count);
 
             // do not check pages >25MB because usually its just a binary file or sth
             if response.text.len() > MAX_PAGE_SIZE && !self.config.force {
@@ -328,8 +367,8 @@ impl<'a> Runner<'a> {
         }
 
         // check the last time
-        let response = Request::new_random(&self.request_defaults, self.max)
-            .send()
+        let response = self
+            .send_with_throttling(Request::new_random(&self.request_defaults, self.max))
             .await?;
 
         // in case the page is still different from other random ones - the body isn't stable
@@ -338,13 +377,7 @@ impl<'a> Runner<'a> {
             .1
             .is_empty()
         {
-            utils::info(
-                self.config,
-                self.id,
-                self.progress_bar,
-                "~",
-                "The page is not stable (body)",
-            );
+            self.warning("The page is not stable (body)");
             stable.body = false;
         }
 
@@ -359,8 +392,8 @@ impl<'a> Runner<'a> {
 
         let delta = self.max / 2;
 
-        let response = Request::new_random(&self.request_defaults, self.max + delta)
-            .send()
+        let response = self
+            .send_with_throttling(Request::new_random(&self.request_defaults, self.max + delta))
             .await?;
 
         let (is_code_different, new_diffs) =
@@ -373,8 +406,8 @@ impl<'a> Runner<'a> {
 
         // in case the page isn't different from previous one - try to increase max amount of parameters by 128
         if !is_code_different && (!self.stable.body || is_the_body_the_same) {
-            let response = Request::new_random(&self.request_defaults, self.max + delta*2)
-                .send()
+            let response = self
+                .send_with_throttling(Request::new_random(&self.request_defaults, self.max + delta*2))
                 .await?;
 
             let (is_code_different, new_diffs) =
@@ -400,8 +433,8 @@ impl<'a> Runner<'a> {
 
         let mut max = 128;
 
-        let mut response = match Request::new_random(&self.request_defaults, max)
-            .send()
+        let mut response = match self
+            .send_with_throttling(Request::new_random(&self.request_defaults, max))
             .await {
                 Ok(val) => val,
                 // some servers may cut connection in case url is too long
@@ -417,7 +450,7 @@ impl<'a> Runner<'a> {
                 break
             }
 
-            if Request::new_random(&self.request_defaults, 0).send().await?.code != self.initial_response.code {
+            if self.send_with_throttling(Request::new_random(&self.request_defaults, 0)).await?.code != self.initial_response.code {
                 Err("The page became unstable (code)")?
             };
 
@@ -427,8 +460,8 @@ impl<'a> Runner<'a> {
                 Err("Unable to guess the max amount of parameters per request. Try to use --max command line argument.")?
             }
 
-            response = match Request::new_random(&self.request_defaults, max)
-                .send()
+            response = match self
+                .send_with_throttling(Request::new_random(&self.request_defaults, max))
                 .await {
                     Ok(val) => val,
                     Err(_) => {
@@ -437,67 +470,16 @@ impl<'a> Runner<'a> {
             };
         }
 
-        Ok(max as isize *-1)
+        Ok(-(max as isize))
     }
 
-    pub fn prepare_progress_bar(&self, sty: ProgressStyle, length: usize) {
-        self.progress_bar.reset();
-        self.progress_bar.set_prefix(self.make_progress_prefix());
-        self.progress_bar.set_style(sty);
-        self.progress_bar.set_length(length as u64);
-    }
-
-    fn make_progress_prefix(&self) -> String {
-        // to align all the progress bars
-        let id = if is_id_important(self.config) {
-            let mut id = self.id.to_string() + ":";
-            id += &" ".repeat(1 + self.config.urls.len().to_string().len() - id.to_string().len());
-            format!("{} ", id.replace(&self.id.to_string(), &color_id(self.id)))
-        } else {
-            String::new()
-        };
-
-        let mut method = self.request_defaults.method.clone();
-        method +=
-            &" ".repeat(self.config.methods.iter().map(|x| x.len()).max().unwrap() - method.len());
-
-        format!(
-            "{}{} {}",
-            id,
-            method.blue(),
-            fold_url(
-                &self.request_defaults.url_without_default_port(),
-                DEFAULT_PROGRESS_URL_MAX_LEN
-            )
-            .green()
-        )
+    /// passes intermediate information to the observer
+    pub fn info<T: Display>(&self, kind: &str, msg: T) {
+        self.observer.on_info(&self.target, kind, &msg.to_string());
     }
 
-    pub fn write_banner_url(&self) {
-
-        let id = if is_id_important(self.config) {
-            format!("[{}] ", color_id(self.id))
-        } else {
-            String::new()
-        };
-
-        let msg = format!(
-            "{}{} {} ({}) [{}] {{{}}}",
-            id,
-            self.request_defaults.method.blue(),
-            self.request_defaults.url_without_default_port().green(),
-            self.initial_response.code(),
-            self.initial_response.text.len().to_string().green(),
-            self.request_defaults
-                .amount_of_reflections
-                .to_string()
-                .magenta()
-        );
-
-        if self.config.disable_progress_bar {
-            writeln!(io::stdout(), "{}", msg).ok();
-        } else {
-            self.progress_bar.println(msg);
-        }
+    /// passes non critical errors to the observer
+    pub fn warning<T: Display>(&self, msg: T) {
+        self.observer.on_warning(&self.target, &msg.to_string());
     }
 }
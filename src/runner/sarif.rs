@@ -0,0 +1,126 @@
+use serde_json::{json, Value};
+
+use super::{
+    baseline::BaselineStatus,
+    output::RunnerOutput,
+    utils::{FoundParameter, ReasonKind},
+};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// (reason kind, rule id, description). The index within the array is the rule index
+const RULES: [(ReasonKind, &str, &str); 4] = [
+    (ReasonKind::Code, "code-change", "The parameter changes the page's status code"),
+    (ReasonKind::Text, "body-change", "The parameter changes the page's body or headers"),
+    (ReasonKind::Reflected, "reflection", "The parameter is reflected on the page"),
+    (
+        ReasonKind::NotReflected,
+        "reflection-change",
+        "The parameter changes the amount of reflections of other parameters",
+    ),
+];
+
+/// converts outputs to a SARIF 2.1.0 log with a result per every found parameter.
+/// Parameters gone since the baseline are added as absent results
+pub fn sarif(outputs: &[RunnerOutput]) -> String {
+    let rules: Vec<Value> = RULES
+        .iter()
+        .map(|(_, id, description)| {
+            json!({
+                "id": id,
+                "name": id,
+                "shortDescription": { "text": description },
+                "defaultConfiguration": { "level": "note" },
+            })
+        })
+        .collect();
+
+    let results: Vec<Value> = outputs
+        .iter()
+        .flat_map(|output| {
+            output
+                .found_params
+                .iter()
+                .chain(output.gone_params.iter())
+                .map(move |x| result(output, x))
+        })
+        .collect();
+
+    let log = json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "x8",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://github.com/Sh1Yo/x8",
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }]
+    });
+
+    serde_json::to_string_pretty(&log).unwrap()
+}
+
+fn result(output: &RunnerOutput, parameter: &FoundParameter) -> Value {
+    let rule_index = RULES
+        .iter()
+        .position(|x| x.0 == parameter.reason_kind)
+        .unwrap();
+
+    let name = match &parameter.value {
+        Some(value) => format!("{}={}", parameter.name, value),
+        None => parameter.name.clone(),
+    };
+
+    let message = match parameter.reason_kind {
+        ReasonKind::Code => format!("{} changes the status code: {} -> {}", name, output.status, parameter.status),
+        ReasonKind::Text => format!("{} changes the page: {} -> {} bytes", name, output.size, parameter.size),
+        ReasonKind::Reflected => format!("{} is reflected on the page", name),
+        ReasonKind::NotReflected => format!("{} changes the amount of reflections", name),
+    };
+
+    let mut result = json!({
+        "ruleId": RULES[rule_index].1,
+        "ruleIndex": rule_index,
+        "level": "note",
+        "message": { "text": format!("{} {}: {}", output.method, output.url, message) },
+        "locations": [{
+            "physicalLocation": {
+                "artifactLocation": { "uri": output.url }
+            },
+            "logicalLocations": [{
+                "name": format!("{} {}", output.method, output.url),
+                "kind": "resource",
+            }]
+        }],
+        "partialFingerprints": {
+            "parameter/v1": format!("{} {} {}", output.method, output.url, parameter.name),
+        },
+        "properties": {
+            "method": output.method,
+            "injectionPlace": output.injection_place,
+            "parameter": parameter.name,
+            "value": parameter.value,
+            "diffs": parameter.diffs,
+            "status": parameter.status,
+            "size": parameter.size,
+            "initialStatus": output.status,
+            "initialSize": output.size,
+        }
+    });
+
+    // the state compared to the previous scan (--baseline)
+    if let Some(baseline) = parameter.baseline {
+        result["baselineState"] = json!(match baseline {
+            BaselineStatus::New => "new",
+            BaselineStatus::Present => "unchanged",
+            BaselineStatus::Gone => "absent",
+        });
+    }
+
+    result
+}
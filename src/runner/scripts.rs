@@ -0,0 +1,245 @@
+use std::{collections::HashSet, convert::TryFrom, error::Error};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::Value;
+use url::Url;
+
+use crate::{network::utils::Headers, MAX_PAGE_SIZE};
+
+use super::runner::Runner;
+
+/// the max amount of scripts to fetch per url:method pair
+const MAX_SCRIPTS: usize = 32;
+
+lazy_static! {
+    static ref RE_SCRIPT_SRC: Regex =
+        Regex::new(r#"(?i)<script[^>]+?src\s*=\s*["']?([^"'\s>]+)"#).unwrap();
+
+    static ref RE_SOURCE_MAP: Regex =
+        Regex::new(r#"//[#@]\s*sourceMappingURL\s*=\s*(\S+)"#).unwrap();
+
+    /// {key: .., "key": .., 'key': ..}
+    static ref RE_OBJECT_KEYS: Regex =
+        Regex::new(r#"[{,]\s*["']?([A-Za-z_$][\w$-]{1,40})["']?\s*:"#).unwrap();
+
+    /// params.append("key", ..), searchParams.get('key'), formData.set("key", ..)
+    static ref RE_PARAMS_CALLS: Regex = Regex::new(
+        r#"\.(?:append|set|get|getAll|has|delete)\(\s*["'`]([A-Za-z_$][\w$.\[\]-]{0,40})["'`]"#
+    )
+    .unwrap();
+
+    /// fetch("/api?key=" + ..), "&key="
+    static ref RE_QUERY_KEYS: Regex =
+        Regex::new(r#"[?&]([A-Za-z_$][\w$.\[\]-]{0,40})="#).unwrap();
+}
+
+impl<'a> Runner<'a> {
+    /// fetches same-origin scripts referenced within the initial response (and their source maps)
+    /// and adds the keys found within them to the possible parameters
+    pub async fn mine_scripts(&mut self) {
+        let base = match Url::parse(&self.request_defaults.url()) {
+            Ok(val) => val,
+            Err(_) => return,
+        };
+
+        for script_url in script_urls(&self.initial_response.text, &base) {
+            let (script, headers) = match self.fetch(&script_url).await {
+                Ok(val) => val,
+                Err(err) => {
+                    self.warning(format!("unable to fetch {}: {}", script_url, err));
+                    continue;
+                }
+            };
+
+            self.add_mined_params(extract_parameters(&script), script_url.as_str());
+
+            let header = headers
+                .get_value_case_insensitive("sourcemap")
+                .or_else(|| headers.get_value_case_insensitive("x-sourcemap"));
+
+            let source_map = match source_map_url(&script, header, &script_url) {
+                Some(val) => val,
+                None => continue,
+            };
+
+            // inline source maps are within the script itself
+            let source_map = if let Some(content) = inline_source_map(source_map.as_str()) {
+                content
+            } else if source_map.origin() == base.origin() {
+                match self.fetch(&source_map).await {
+                    Ok((val, _)) => val,
+                    Err(_) => continue,
+                }
+            } else {
+                continue;
+            };
+
+            self.add_mined_params(
+                extract_from_source_map(&source_map),
+                &format!("{} (source map)", script_url),
+            );
+        }
+    }
+
+    fn add_mined_params(&mut self, params: Vec<String>, source: &str) {
+        let mut amount = 0;
+
+        for param in params {
+            if !self.possible_params.contains(&param) {
+                self.possible_params_sources.insert(param.clone(), source.to_string());
+                self.possible_params.push(param);
+                amount += 1;
+            }
+        }
+
+        if amount != 0 {
+            self.info("info", format!("{} parameters were mined from {}", amount, source));
+        }
+    }
+
+    /// makes a GET request via the runner's client with the user supplied headers
+    async fn fetch(&self, url: &Url) -> Result<(String, Vec<(String, String)>), Box<dyn Error>> {
+        let mut request = http::Request::builder().method("GET").uri(url.as_str());
+
+        for (k, v) in self.request_defaults.custom_headers.iter() {
+            // headers with the injection point and body related ones aren't needed
+            if k.contains("%s")
+                || v.contains("%s")
+                || ["content-type", "content-length"].contains(&k.to_lowercase().as_str())
+            {
+                continue;
+            }
+            request = request.header(k, v);
+        }
+
+        tokio::time::sleep(self.request_defaults.delay).await;
+        self.request_defaults
+            .rate_limiter
+            .wait(url.host_str().unwrap_or_default())
+            .await;
+
+        let response = self
+            .request_defaults
+            .client
+            .execute(reqwest::Request::try_from(request.body(String::new())?)?)
+            .await?;
+
+        if !response.status().is_success() {
+            Err(format!("status code {}", response.status().as_u16()))?;
+        }
+
+        let headers = response
+            .headers()
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or_default().to_string()))
+            .collect();
+
+        let body = response.bytes().await?;
+        if body.len() > MAX_PAGE_SIZE {
+            Err("the file is too big")?;
+        }
+
+        Ok((String::from_utf8_lossy(&body).to_string(), headers))
+    }
+}
+
+/// returns same-origin urls from <script src=..> tags except for the bundled dependencies
+pub(super) fn script_urls(html: &str, base: &Url) -> Vec<Url> {
+    let mut urls: Vec<Url> = Vec::new();
+
+    for cap in RE_SCRIPT_SRC.captures_iter(html) {
+        let url = match base.join(&cap[1]) {
+            Ok(val) => val,
+            Err(_) => continue,
+        };
+
+        if url.origin() == base.origin() && !is_dependency(url.path()) && !urls.contains(&url) {
+            urls.push(url);
+        }
+    }
+
+    urls.truncate(MAX_SCRIPTS);
+    urls
+}
+
+/// returns the url from the SourceMap header or the sourceMappingURL comment
+pub(super) fn source_map_url(script: &str, header: Option<String>, script_url: &Url) -> Option<Url> {
+    let source_map = header.or_else(|| {
+        RE_SOURCE_MAP
+            .captures_iter(script)
+            .last()
+            .map(|x| x[1].to_string())
+    })?;
+
+    script_url.join(&source_map).ok()
+}
+
+/// decodes data:application/json;base64,.. source maps
+fn inline_source_map(url: &str) -> Option<String> {
+    let data = url.strip_prefix("data:")?;
+    let (media_type, content) = data.split_once(',')?;
+
+    if !media_type.ends_with(";base64") {
+        return None;
+    }
+
+    let decoded = STANDARD.decode(content).ok()?;
+    Some(String::from_utf8_lossy(&decoded).to_string())
+}
+
+/// dependencies like vendor bundles would add third-party identifiers to the wordlist
+fn is_dependency(path: &str) -> bool {
+    let path = path.to_lowercase();
+
+    path.starts_with("webpack/") || ["node_modules", "vendor", "jquery", "polyfill"].iter().any(|x| path.contains(x))
+}
+
+/// extracts parameters from the original sources except for the dependencies
+pub(super) fn extract_from_source_map(source_map: &str) -> Vec<String> {
+    let source_map: Value = match serde_json::from_str(source_map) {
+        Ok(val) => val,
+        Err(_) => return Vec::new(),
+    };
+
+    let sources = source_map.get("sources").and_then(|x| x.as_array());
+    let contents = match source_map.get("sourcesContent").and_then(|x| x.as_array()) {
+        Some(val) => val,
+        None => return Vec::new(),
+    };
+
+    let mut params: Vec<String> = Vec::new();
+
+    for (i, content) in contents.iter().enumerate() {
+        let source = sources
+            .and_then(|x| x.get(i))
+            .and_then(|x| x.as_str())
+            .unwrap_or_default();
+
+        if is_dependency(source) {
+            continue;
+        }
+
+        params.append(&mut extract_parameters(content.as_str().unwrap_or_default()));
+    }
+
+    dedup(params)
+}
+
+/// extracts candidate keys from object literals, URLSearchParams/FormData calls, and query strings
+pub(super) fn extract_parameters(script: &str) -> Vec<String> {
+    dedup(
+        RE_OBJECT_KEYS
+            .captures_iter(script)
+            .chain(RE_PARAMS_CALLS.captures_iter(script))
+            .chain(RE_QUERY_KEYS.captures_iter(script))
+            .map(|x| x[1].to_string())
+            .collect(),
+    )
+}
+
+fn dedup(params: Vec<String>) -> Vec<String> {
+    let mut seen = HashSet::with_capacity(params.len());
+    params.into_iter().filter(|x| seen.insert(x.clone())).collect()
+}
@@ -0,0 +1,173 @@
+use std::{collections::HashMap, error::Error, fs};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::{network::request::RequestDefaults, utils::fnv_hash};
+
+use super::{
+    output::RunnerOutput,
+    utils::{FoundParameter, Parameters},
+};
+
+/// progress of the whole scan. Stored as json within the --state file
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct State {
+    /// outputs of finished url:method pairs
+    pub finished: HashMap<String, RunnerOutput>,
+
+    /// progress of unfinished url:method pairs
+    pub runners: HashMap<String, RunnerState>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct RunnerState {
+    /// processed chunks are valid only in case the amount of parameters per request
+    /// and the list of parameters are the same
+    pub max: usize,
+
+    /// see params_hash()
+    #[serde(default)]
+    pub params_hash: u64,
+
+    /// indexes of processed chunks
+    pub chunks: Vec<usize>,
+
+    /// parameters found within processed chunks
+    pub found_params: Vec<FoundParameter>,
+}
+
+/// state shared between all the runners. Every change is immediately written to the file
+#[derive(Debug)]
+pub struct StateFile {
+    filename: String,
+    state: Mutex<State>,
+}
+
+impl State {
+    /// targets are identified by the method, the url with the injection point and the hash of headers and body.
+    /// Several request files can share the method and the url
+    pub fn key(request_defaults: &RequestDefaults) -> String {
+        format!(
+            "{} {} {:016x}",
+            request_defaults.method,
+            request_defaults.url(),
+            request_defaults.target_hash()
+        )
+    }
+}
+
+/// hash of the list of parameters
+pub fn params_hash(params: &[String]) -> u64 {
+    fnv_hash(params.iter().map(|x| x.as_str()))
+}
+
+impl StateFile {
+    /// creates a new state file or continues the existing one in case resume is true
+    pub fn new(filename: &str, resume: bool) -> Result<Self, Box<dyn Error>> {
+        let state = if resume {
+            match serde_json::from_str(&fs::read_to_string(filename)?) {
+                Ok(val) => val,
+                Err(err) => Err(format!("Unable to parse the state file: {}", err))?,
+            }
+        } else {
+            State::default()
+        };
+
+        let state_file = Self {
+            filename: filename.to_string(),
+            state: Mutex::new(state),
+        };
+
+        state_file.save(&state_file.state.lock())?;
+
+        Ok(state_file)
+    }
+
+    /// writes to a temporary file first so the state isn't lost in case the tool is killed while writing
+    fn save(&self, state: &State) -> Result<(), Box<dyn Error>> {
+        let tmp_filename = format!("{}.tmp", self.filename);
+
+        fs::write(&tmp_filename, serde_json::to_string(state)?)?;
+        fs::rename(&tmp_filename, &self.filename)?;
+
+        Ok(())
+    }
+
+    /// returns the output in case the url:method pair was already checked
+    pub fn finished(&self, request_defaults: &RequestDefaults) -> Option<RunnerOutput> {
+        self.state
+            .lock()
+            .finished
+            .get(&State::key(request_defaults))
+            .cloned()
+    }
+
+    /// returns already processed chunks and parameters found within them
+    pub fn runner_state(
+        &self,
+        request_defaults: &RequestDefaults,
+        max: usize,
+        params: &[String],
+    ) -> Option<RunnerState> {
+        let params_hash = params_hash(params);
+
+        self.state
+            .lock()
+            .runners
+            .get(&State::key(request_defaults))
+            .filter(|x| x.max == max && x.params_hash == params_hash)
+            .cloned()
+    }
+
+    /// found_params should contain only the parameters found within the chunk,
+    /// because other chunks are checked again after --resume in case they weren't finished
+    pub fn save_chunk(
+        &self,
+        request_defaults: &RequestDefaults,
+        max: usize,
+        params: &[String],
+        chunk: usize,
+        found_params: &[FoundParameter],
+    ) -> Result<(), Box<dyn Error>> {
+        let params_hash = params_hash(params);
+        let mut state = self.state.lock();
+
+        let runner_state = state
+            .runners
+            .entry(State::key(request_defaults))
+            .or_default();
+
+        // the list of parameters has changed (for example, within the recursive search)
+        if runner_state.max != max || runner_state.params_hash != params_hash {
+            *runner_state = RunnerState {
+                max,
+                params_hash,
+                ..Default::default()
+            };
+        }
+
+        runner_state.chunks.push(chunk);
+        for found_param in found_params {
+            if !runner_state.found_params.contains_name(&found_param.name) {
+                runner_state.found_params.push(found_param.clone());
+            }
+        }
+
+        self.save(&state)
+    }
+
+    pub fn save_finished(
+        &self,
+        request_defaults: &RequestDefaults,
+        runner_output: &RunnerOutput,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut state = self.state.lock();
+        let key = State::key(request_defaults);
+
+        state.runners.remove(&key);
+        state.finished.insert(key, runner_output.clone());
+
+        self.save(&state)
+    }
+}
@@ -0,0 +1,96 @@
+use super::{output::RunnerOutput, utils::FoundParameter};
+
+/// output formats with a row per found parameter. The header is written only once
+pub const TABLE_OUTPUT_FORMATS: [&str; 2] = ["csv", "markdown"];
+
+const COLUMNS: [&str; 10] = [
+    "method",
+    "url",
+    "injection_place",
+    "name",
+    "value",
+    "reason_kind",
+    "status",
+    "size",
+    "diffs",
+    "baseline",
+];
+
+/// the header of the table. Markdown tables contain the delimiter row as well
+pub fn header(format: &str) -> String {
+    if format == "markdown" {
+        format!(
+            "{}\n|{}\n",
+            markdown_row(&COLUMNS.map(String::from)),
+            "---|".repeat(COLUMNS.len())
+        )
+    } else {
+        csv_row(&COLUMNS.map(String::from)) + "\n"
+    }
+}
+
+/// a row per every found parameter of the output and per every parameter gone since the baseline
+pub fn rows(output: &RunnerOutput, format: &str) -> String {
+    output
+        .found_params
+        .iter()
+        .chain(output.gone_params.iter())
+        .map(|x| {
+            let cells = cells(output, x);
+            let row = if format == "markdown" {
+                markdown_row(&cells)
+            } else {
+                csv_row(&cells)
+            };
+            row + "\n"
+        })
+        .collect()
+}
+
+fn cells(output: &RunnerOutput, parameter: &FoundParameter) -> [String; 10] {
+    [
+        output.method.clone(),
+        output.url.clone(),
+        format!("{:?}", output.injection_place),
+        parameter.name.clone(),
+        parameter.value.clone().unwrap_or_default(),
+        format!("{:?}", parameter.reason_kind),
+        parameter.status.to_string(),
+        parameter.size.to_string(),
+        parameter.diffs.clone(),
+        parameter.baseline.map(|x| x.as_str()).unwrap_or_default().to_string(),
+    ]
+}
+
+/// fields with commas, quotes or line breaks are quoted as described in RFC 4180.
+/// Fields that spreadsheets would treat as formulas are prefixed with '
+fn csv_row(cells: &[String]) -> String {
+    cells
+        .iter()
+        .map(|x| {
+            let x = if x.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+                format!("'{}", x)
+            } else {
+                x.to_owned()
+            };
+
+            if x.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", x.replace('"', "\"\""))
+            } else {
+                x
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+fn markdown_row(cells: &[String]) -> String {
+    format!(
+        "| {} |",
+        cells
+            .iter()
+            .map(|x| x.replace('|', "\\|").replace(['\r', '\n'], " "))
+            .collect::<Vec<String>>()
+            .join(" | ")
+    )
+}
@@ -0,0 +1,286 @@
+#[cfg(test)]
+mod tests {
+    use url::Url;
+
+    use crate::{
+        network::{request::RequestDefaults, utils::InjectionPlace},
+        runner::{
+            baseline::{Baseline, BaselineStatus},
+            html::html,
+            output::{output_url, OutputEvent, RunnerOutput},
+            sarif::sarif,
+            state::StateFile,
+            scripts::{extract_from_source_map, extract_parameters, script_urls, source_map_url},
+            table,
+            utils::{FoundParameter, ReasonKind},
+        },
+    };
+
+    /// the output of a path target with 200 initial status
+    fn runner_output(method: &str, url: &str, found_params: Vec<FoundParameter>) -> RunnerOutput {
+        RunnerOutput {
+            method: method.to_string(),
+            url: url.to_string(),
+            status: 200,
+            size: 25,
+            found_params,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn script_parameters() {
+        let base = Url::parse("https://example.com/app/").unwrap();
+        let html = r#"<script src="/static/main.js"></script>
+<script type="module" src='chunk.js'></script>
+<script src="https://cdn.example.org/lib.js"></script>
+<script src="/static/js/chunk-vendors.3f2a.js"></script>
+<script src="/static/main.js"></script>"#;
+
+        assert_eq!(
+            script_urls(html, &base),
+            vec![
+                Url::parse("https://example.com/static/main.js").unwrap(),
+                Url::parse("https://example.com/app/chunk.js").unwrap(),
+            ]
+        );
+
+        let script = r#"fetch("/api/users?role=" + r + "&includeDeleted=1", {method:"POST",body:JSON.stringify({"userId":1,'tenant':t})});
+const p = new URLSearchParams(); p.append("debugMode", "1"); u.searchParams.get('redirect_to');
+//# sourceMappingURL=main.js.map"#;
+
+        assert_eq!(
+            extract_parameters(script),
+            vec!["method", "body", "userId", "tenant", "debugMode", "redirect_to", "role", "includeDeleted"]
+        );
+
+        assert_eq!(
+            source_map_url(script, None, &Url::parse("https://example.com/static/main.js").unwrap()),
+            Some(Url::parse("https://example.com/static/main.js.map").unwrap())
+        );
+
+        let source_map = r#"{
+            "sources": ["webpack:///./src/api.js", "webpack:///./node_modules/lib/index.js"],
+            "sourcesContent": ["api.get('/orders', {params: {orderId: id}})", "x = {internalLib: 1}"]
+        }"#;
+        assert_eq!(extract_from_source_map(source_map), vec!["params", "orderId"]);
+    }
+
+    #[test]
+    fn jsonl_events() {
+        let parameter = FoundParameter::new("debug=true", &["-1,1 +1,1".to_string()], 200, 31, ReasonKind::Text);
+
+        let line = serde_json::to_string(&OutputEvent::Finding {
+            method: "GET",
+            url: output_url("https://example.com/?%s", InjectionPlace::Path),
+            injection_place: InjectionPlace::Path,
+            parameter: &parameter,
+            initial_status: 200,
+            initial_size: 25,
+        })
+        .unwrap();
+
+        assert_eq!(
+            line,
+            r#"{"event":"finding","method":"GET","url":"https://example.com/","injection_place":"Path","name":"debug","value":"true","diffs":"-1,1 +1,1","status":200,"size":31,"reason_kind":"Text","initial_status":200,"initial_size":25}"#
+        );
+        assert!(!line.contains('\n'));
+    }
+
+    #[test]
+    fn sarif_output() {
+        let mut output = runner_output(
+            "POST",
+            "https://example.com/api",
+            vec![
+                FoundParameter::new("admin", &["200 -> 403".to_string()], 403, 10, ReasonKind::Code),
+                FoundParameter::new("q", &[], 200, 30, ReasonKind::Reflected),
+            ],
+        );
+        output.injection_place = InjectionPlace::Body;
+
+        let log: serde_json::Value = serde_json::from_str(&sarif(&[output])).unwrap();
+        let results = log["runs"][0]["results"].as_array().unwrap();
+
+        assert_eq!(log["version"], "2.1.0");
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0]["ruleId"], "code-change");
+        assert_eq!(results[1]["ruleId"], "reflection");
+        assert_eq!(
+            log["runs"][0]["tool"]["driver"]["rules"][results[1]["ruleIndex"].as_u64().unwrap() as usize]["id"],
+            "reflection"
+        );
+        assert_eq!(results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"], "https://example.com/api");
+        assert_eq!(results[0]["properties"]["diffs"], "200 -> 403");
+        assert_eq!(results[0]["properties"]["status"], 403);
+        assert_eq!(results[0]["properties"]["method"], "POST");
+    }
+
+    #[test]
+    fn html_report() {
+        let mut found = FoundParameter::new("<b>", &["-1 +1,<i>".to_string()], 200, 40, ReasonKind::Text);
+        found.saved_to = Some("responses/example.com-b".to_string());
+
+        let output = runner_output("GET", "https://example.com/?a=1&b=2", vec![found]);
+
+        let page = html(&[output]);
+
+        assert!(page.contains("https://example.com/?a=1&amp;b=2"));
+        assert!(page.contains("<code>&lt;b&gt;</code>"));
+        assert!(page.contains("<code>-1 +1,&lt;i&gt;</code>"));
+        assert!(page.contains("data-reason=\"text\""));
+        assert!(page.contains("<a href=\"responses/example.com-b\">"));
+        assert!(!page.contains("<b>"));
+    }
+
+    #[test]
+    fn table_rows() {
+        let output = runner_output(
+            "GET",
+            "https://example.com/",
+            vec![FoundParameter::new("debug=true", &["-1,1 +1,1".to_string(), "\"a|b\"".to_string()], 200, 31, ReasonKind::Text)],
+        );
+
+        assert_eq!(
+            table::header("csv"),
+            "method,url,injection_place,name,value,reason_kind,status,size,diffs,baseline\n"
+        );
+        assert_eq!(
+            table::rows(&output, "csv"),
+            "GET,https://example.com/,Path,debug,true,Text,200,31,\"'-1,1 +1,1|\"\"a|b\"\"\",\n"
+        );
+        assert!(table::header("markdown").ends_with("|---|---|---|---|---|---|---|---|---|---|\n"));
+        assert_eq!(
+            table::rows(&output, "markdown"),
+            "| GET | https://example.com/ | Path | debug | true | Text | 200 | 31 | -1,1 +1,1\\|\"a\\|b\" |  |\n"
+        );
+
+        // a leading tab or carriage return starts a formula as well
+        let output = runner_output(
+            "GET",
+            "https://example.com/",
+            vec![FoundParameter::new("x=\tcmd", &["a\r\nb".to_string()], 200, 31, ReasonKind::Text)],
+        );
+        assert!(table::rows(&output, "csv").contains(",x,'\tcmd,"));
+        assert!(table::rows(&output, "markdown").contains(" | a  b | "));
+    }
+
+    #[test]
+    fn baseline_comparison() {
+        let output = |found_params: Vec<FoundParameter>| runner_output("GET", "https://example.com/", found_params);
+
+        let baseline = Baseline::from_outputs(vec![output(vec![
+            FoundParameter::new("debug", &[], 200, 30, ReasonKind::Reflected),
+            FoundParameter::new("admin", &[], 403, 10, ReasonKind::Code),
+        ])]);
+
+        let mut current = output(vec![
+            FoundParameter::new("debug", &[], 200, 30, ReasonKind::Reflected),
+            FoundParameter::new("test", &[], 200, 30, ReasonKind::Reflected),
+        ]);
+        baseline.compare(&mut current, false);
+
+        assert_eq!(current.found_params[0].baseline, Some(BaselineStatus::Present));
+        assert_eq!(current.found_params[1].baseline, Some(BaselineStatus::New));
+        assert_eq!(current.gone_params.len(), 1);
+        assert_eq!(current.gone_params[0].name, "admin");
+        assert_eq!(current.gone_params[0].baseline, Some(BaselineStatus::Gone));
+
+        // every output format contains the baseline status and gone parameters
+        assert!(table::rows(&current, "csv").ends_with(",test,,Reflected,200,30,,new\nGET,https://example.com/,Path,admin,,Code,403,10,,gone\n"));
+
+        let page = html(std::slice::from_ref(&current));
+        assert!(page.contains("<tr class=\"param gone\" data-reason=\"code\">"));
+        assert!(page.contains("<td class=\"new\">new</td>"));
+
+        let log: serde_json::Value = serde_json::from_str(&sarif(std::slice::from_ref(&current))).unwrap();
+        let results = log["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0]["baselineState"], "unchanged");
+        assert_eq!(results[1]["baselineState"], "new");
+        assert_eq!(results[2]["baselineState"], "absent");
+
+        let mut only_new = output(vec![
+            FoundParameter::new("debug", &[], 200, 30, ReasonKind::Reflected),
+            FoundParameter::new("test", &[], 200, 30, ReasonKind::Reflected),
+        ]);
+        baseline.compare(&mut only_new, true);
+
+        assert_eq!(only_new.found_params.len(), 1);
+        assert_eq!(only_new.found_params[0].name, "test");
+        assert!(only_new.gone_params.is_empty());
+
+        // the url:method pair wasn't checked before
+        let mut other = output(vec![FoundParameter::new("debug", &[], 200, 30, ReasonKind::Reflected)]);
+        other.method = "POST".to_string();
+        baseline.compare(&mut other, false);
+
+        assert_eq!(other.found_params[0].baseline, Some(BaselineStatus::New));
+
+        // the same url:method pair with a different body
+        let mut other_body = output(vec![FoundParameter::new("debug", &[], 200, 30, ReasonKind::Reflected)]);
+        other_body.target_hash = 1;
+        let baseline = Baseline::from_outputs(vec![current.clone()]);
+        baseline.compare(&mut other_body, false);
+
+        assert_eq!(other_body.found_params[0].baseline, Some(BaselineStatus::New));
+        assert_eq!(baseline.status("GET", "https://example.com/", 0, "debug"), BaselineStatus::Present);
+        assert_eq!(baseline.status("GET", "https://example.com/", 1, "debug"), BaselineStatus::New);
+    }
+
+    #[test]
+    fn state_resume() {
+        let filename = std::env::temp_dir()
+            .join(format!("x8-state-{}.json", std::process::id()))
+            .to_string_lossy()
+            .to_string();
+
+        let request_defaults = RequestDefaults {
+            method: "GET".to_string(),
+            host: "example.com".to_string(),
+            ..Default::default()
+        };
+
+        let params: Vec<String> = ["a", "b", "c", "admin=true"].iter().map(|x| x.to_string()).collect();
+        let admin = FoundParameter::new("admin=true", &[], 200, 30, ReasonKind::Text);
+
+        let state = StateFile::new(&filename, false).unwrap();
+        state
+            .save_chunk(&request_defaults, 2, &params, 1, std::slice::from_ref(&admin))
+            .unwrap();
+        // the same parameter isn't saved twice
+        state
+            .save_chunk(&request_defaults, 2, &params, 0, &[admin])
+            .unwrap();
+
+        let resumed = StateFile::new(&filename, true).unwrap();
+        let runner_state = resumed.runner_state(&request_defaults, 2, &params).unwrap();
+
+        assert_eq!(runner_state.chunks, vec![1, 0]);
+        assert_eq!(runner_state.found_params.len(), 1);
+        assert_eq!(runner_state.found_params[0].value.as_deref(), Some("true"));
+
+        // a different list of the same length or a different amount of parameters per request
+        let other: Vec<String> = ["a", "b", "c", "d"].iter().map(|x| x.to_string()).collect();
+        assert!(resumed.runner_state(&request_defaults, 2, &other).is_none());
+        assert!(resumed.runner_state(&request_defaults, 4, &params).is_none());
+
+        let output = runner_output("GET", "https://example.com/", runner_state.found_params);
+        resumed.save_finished(&request_defaults, &output).unwrap();
+
+        let finished = StateFile::new(&filename, true).unwrap();
+        assert!(finished.runner_state(&request_defaults, 2, &params).is_none());
+        assert_eq!(finished.finished(&request_defaults).unwrap().found_params[0].name, "admin");
+
+        // request files with the same method and url but a different body are different targets
+        let mut other_body = request_defaults.clone();
+        other_body.body = "user=guest".to_string();
+        assert!(finished.finished(&other_body).is_none());
+
+        finished.save_chunk(&other_body, 2, &params, 0, &[]).unwrap();
+        assert_eq!(finished.runner_state(&other_body, 2, &params).unwrap().chunks, vec![0]);
+        assert_eq!(finished.finished(&request_defaults).unwrap().found_params[0].name, "admin");
+
+        std::fs::remove_file(&filename).ok();
+    }
+}
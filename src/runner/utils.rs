@@ -5,7 +5,7 @@ use std::{
 use lazy_static::lazy_static;
 use regex::Regex;
 use reqwest::Client;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use colored::Colorize;
 
 use crate::{
@@ -18,13 +18,25 @@ use crate::{
     utils::random_line, VALUE_LENGTH,
 };
 
+use super::baseline::BaselineStatus;
+
 #[derive(Debug, Default)]
 pub struct Stable {
     pub body: bool,
     pub reflections: bool,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+/// the host was paused because it limited the rate of requests
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Slowdown {
+    /// what the rate limit was detected by
+    pub reason: String,
+
+    /// in seconds
+    pub pause: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ReasonKind {
     Code,
     Text,
@@ -32,7 +44,7 @@ pub enum ReasonKind {
     NotReflected,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FoundParameter {
     pub name: String,
 
@@ -43,6 +55,14 @@ pub struct FoundParameter {
     pub status: u16,
     pub size: usize,
     pub reason_kind: ReasonKind,
+
+    /// the file with the request and response (--save-responses)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub saved_to: Option<String>,
+
+    /// the parameter compared to the previous scan (--baseline)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub baseline: Option<BaselineStatus>,
 }
 
 impl FoundParameter {
@@ -72,6 +92,8 @@ impl FoundParameter {
             status,
             size,
             reason_kind,
+            saved_to: None,
+            baseline: None,
         }
     }
 
@@ -92,8 +114,8 @@ impl FoundParameter {
             ReasonKind::NotReflected => self.name.bright_cyan(),
         };
 
-        if self.value.is_some() {
-            format!("{}={}", param, self.value.as_ref().unwrap())
+        if let Some(value) = &self.value {
+            format!("{}={}", param, value)
         } else {
             param.to_string()
         }
@@ -187,7 +209,7 @@ impl Parameters for Vec<FoundParameter> {
 }
 
 /// replays a request with parameters via a different proxy
-pub(super) async fn replay<'a>(
+pub(super) async fn replay(
     config: &Config,
     request_defaults: &RequestDefaults,
     replay_client: &Client,
@@ -226,8 +248,8 @@ pub(super) async fn replay<'a>(
 pub(super) async fn verify<'a>(
     initial_response: &'a Response<'a>,
     request_defaults: &'a RequestDefaults,
-    found_params: &Vec<FoundParameter>,
-    diffs: &Vec<String>,
+    found_params: &[FoundParameter],
+    diffs: &[String],
     stable: &Stable,
 ) -> Result<Vec<FoundParameter>, Box<dyn Error>> {
     let mut filtered_params = Vec::with_capacity(found_params.len());
@@ -358,8 +380,8 @@ impl ParamPatterns {
 pub(super) async fn _smart_verify(
     initial_response: &Response<'_>,
     request_defaults: &RequestDefaults,
-    found_params: &Vec<FoundParameter>,
-    diffs: &Vec<String>,
+    found_params: &[FoundParameter],
+    diffs: &[String],
     stable: &Stable,
 ) -> Result<Vec<FoundParameter>, Box<dyn Error>> {
     let mut filtered_params = Vec::with_capacity(found_params.len());
@@ -371,7 +393,7 @@ pub(super) async fn _smart_verify(
             .send()
             .await?;
 
-        let (is_code_the_same, new_diffs) = response.compare(initial_response, &diffs)?;
+        let (is_code_the_same, new_diffs) = response.compare(initial_response, diffs)?;
         let mut is_the_body_the_same = true;
 
         if !new_diffs.is_empty() {
@@ -389,7 +411,7 @@ pub(super) async fn _smart_verify(
 }
 
 /// returns last n chars of an url
-pub(super) fn fold_url(url: &str, n: usize) -> String {
+pub(crate) fn fold_url(url: &str, n: usize) -> String {
     if url.len() <= n + 2 {
         //we need to add some spaces to align the progress bars
         url.to_string() + &" ".repeat(2 + n - url.len())
@@ -0,0 +1,302 @@
+use std::{collections::HashMap, error::Error, sync::Arc, time::Duration};
+
+use url::Url;
+
+use crate::{
+    config::{
+        mutations::Mutations,
+        structs::{Config, Target},
+        utils::add_default_headers,
+        wordlist::{WordlistFile, Wordlists, WORDLIST_PLACES},
+    },
+    network::{
+        rate::RateLimiter,
+        retry::RetryPolicy,
+        utils::{DataType, Headers},
+    },
+};
+
+use super::scanner::Scanner;
+
+/// builds a scanner without the command line arguments.
+/// The defaults are the same as the command line ones
+#[derive(Debug, Default)]
+pub struct ScannerBuilder {
+    config: Config,
+    urls: Vec<String>,
+    methods: Vec<String>,
+    headers: Vec<(String, String)>,
+    body: String,
+
+    /// targets with their own method, headers and body
+    targets: Vec<Target>,
+
+    /// in-memory wordlists: (injection place, words)
+    words: Vec<(Option<String>, Vec<String>)>,
+}
+
+impl ScannerBuilder {
+    /// url with an optional injection point. Checked with every method
+    pub fn target<S: Into<String>>(mut self, url: S) -> Self {
+        self.urls.push(url.into());
+        self
+    }
+
+    /// adds a prepared request (for example, imported from a file)
+    pub fn request(mut self, target: Target) -> Self {
+        self.targets.push(target);
+        self
+    }
+
+    /// GET by default
+    pub fn method<S: Into<String>>(mut self, method: S) -> Self {
+        self.methods.push(method.into());
+        self
+    }
+
+    pub fn header<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.headers.push((key.into(), value.into()));
+        self
+    }
+
+    pub fn body<S: Into<String>>(mut self, body: S) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// parameters to check within every injection place
+    pub fn wordlist<I, S>(mut self, words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.words.push((None, words.into_iter().map(|x| x.into()).collect()));
+        self
+    }
+
+    /// parameters to check only within the injection place (query, body, json, headers, header-value)
+    pub fn wordlist_for<P, I, S>(mut self, place: P, words: I) -> Self
+    where
+        P: Into<String>,
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.words.push((Some(place.into()), words.into_iter().map(|x| x.into()).collect()));
+        self
+    }
+
+    /// the same as -w: filename, place:filename or builtin:name
+    pub fn wordlist_file<S: AsRef<str>>(mut self, value: S) -> Self {
+        self.config.wordlists.push(WordlistFile::parse(value.as_ref()));
+        self
+    }
+
+    /// amount of concurrent requests per url
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.config.concurrency = concurrency;
+        self
+    }
+
+    /// amount of concurrent url checks
+    pub fn workers(mut self, workers: usize) -> Self {
+        self.config.workers = workers;
+        self
+    }
+
+    /// max amount of parameters per request
+    pub fn max(mut self, max: usize) -> Self {
+        self.config.max = Some(max);
+        self
+    }
+
+    /// http request timeout in seconds
+    pub fn timeout(mut self, timeout: usize) -> Self {
+        self.config.timeout = timeout;
+        self
+    }
+
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.config.delay = delay;
+        self
+    }
+
+    /// min intervals between any two requests and between two requests to the same host
+    pub fn rate(mut self, global: Option<Duration>, per_host: Option<Duration>) -> Self {
+        self.config.rate_limiter = Arc::new(RateLimiter::new(global, per_host));
+        self
+    }
+
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.config.retry_policy = retry_policy;
+        self
+    }
+
+    pub fn proxy<S: Into<String>>(mut self, proxy: S) -> Self {
+        self.config.proxy = proxy.into();
+        self
+    }
+
+    pub fn follow_redirects(mut self, follow_redirects: bool) -> Self {
+        self.config.follow_redirects = follow_redirects;
+        self
+    }
+
+    pub fn http_version(mut self, http_version: http::Version) -> Self {
+        self.config.http_version = Some(http_version);
+        self
+    }
+
+    pub fn learn_requests_count(mut self, count: usize) -> Self {
+        self.config.learn_requests_count = count;
+        self
+    }
+
+    pub fn recursion_depth(mut self, depth: usize) -> Self {
+        self.config.recursion_depth = depth;
+        self
+    }
+
+    pub fn verify(mut self, verify: bool) -> Self {
+        self.config.verify = verify;
+        self
+    }
+
+    pub fn reflected_only(mut self, reflected_only: bool) -> Self {
+        self.config.reflected_only = reflected_only;
+        self
+    }
+
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.config.strict = strict;
+        self
+    }
+
+    /// search for headers or header values (the injection point within headers)
+    pub fn headers_discovery(mut self, headers_discovery: bool) -> Self {
+        self.config.headers_discovery = headers_discovery;
+        self
+    }
+
+    pub fn disable_custom_parameters(mut self, disable: bool) -> Self {
+        self.config.disable_custom_parameters = disable;
+        self
+    }
+
+    pub fn mine_scripts(mut self, mine_scripts: bool) -> Self {
+        self.config.mine_scripts = mine_scripts;
+        self
+    }
+
+    pub fn mutations(mut self, mutations: Mutations) -> Self {
+        self.config.mutations = mutations;
+        self
+    }
+
+    /// compares found parameters with the json output of the previous scan
+    pub fn baseline<S: Into<String>>(mut self, filename: S, only_new: bool) -> Self {
+        self.config.baseline = filename.into();
+        self.config.only_new = only_new;
+        self
+    }
+
+    /// changes any other option of the config
+    pub fn configure<F: FnOnce(&mut Config)>(mut self, f: F) -> Self {
+        f(&mut self.config);
+        self
+    }
+
+    pub fn build(self) -> Result<Scanner, Box<dyn Error>> {
+        let mut config = self.config;
+
+        if self.urls.is_empty() && self.targets.is_empty() {
+            Err("A target was not provided")?;
+        }
+
+        for url in self.urls.iter() {
+            if let Err(err) = Url::parse(url) {
+                Err(format!("Unable to parse {}: {}", url, err))?;
+            }
+        }
+
+        // set default headers if weren't specified by a user.
+        let headers = add_default_headers(
+            self.headers
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.to_owned()))
+                .collect::<HashMap<&str, String>>(),
+        );
+
+        let data_type = if headers.get_value_case_insensitive("content-type")
+            == Some("application/json".to_string())
+        {
+            Some(DataType::ProbablyJson)
+        } else {
+            None
+        };
+
+        if !self.methods.is_empty() {
+            config.methods = self.methods;
+        }
+
+        config.targets = Vec::with_capacity(self.urls.len() * config.methods.len() + self.targets.len());
+        for url in self.urls.iter() {
+            for method in config.methods.iter() {
+                config.targets.push(Target {
+                    method: method.to_owned(),
+                    url: url.to_owned(),
+                    headers: headers.clone(),
+                    body: self.body.clone(),
+                    data_type: data_type.clone(),
+                    parameters: Vec::new(),
+                    raw_parameters: Vec::new(),
+                    invert: false,
+                    raw: None,
+                    http_version: None,
+                });
+            }
+        }
+
+        config.urls = self.urls;
+        for target in self.targets {
+            if !config.urls.contains(&target.url) {
+                config.urls.push(target.url.clone());
+            }
+            if !config.methods.contains(&target.method) {
+                config.methods.push(target.method.clone());
+            }
+            config.targets.push(target);
+        }
+
+        config.custom_headers = headers;
+        config.body = self.body;
+        config.data_type = data_type;
+
+        // never reads stdin, unlike the command line interface
+        let mut wordlists = if config.wordlists.is_empty() && self.words.is_empty() {
+            Wordlists::builtin()?
+        } else {
+            Wordlists::default()
+        };
+
+        for wordlist in config.wordlists.iter() {
+            wordlists.add(wordlist.place.as_deref(), wordlist.read()?);
+        }
+
+        for (place, words) in self.words {
+            if let Some(place) = &place {
+                if !WORDLIST_PLACES.contains(&place.as_str()) {
+                    Err(format!(
+                        "Unknown injection place: {}. Available: {}",
+                        place,
+                        WORDLIST_PLACES.join(", ")
+                    ))?;
+                }
+            }
+            wordlists.add(place.as_deref(), words);
+        }
+
+        let wordlists = wordlists.prepare(&config);
+
+        Scanner::new(config, wordlists)
+    }
+}
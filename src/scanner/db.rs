@@ -0,0 +1,537 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    io::{self, Write},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use colored::Colorize;
+use parking_lot::Mutex;
+use rusqlite::{params, Connection, OpenFlags};
+use serde_json::json;
+use url::Url;
+
+use crate::{
+    config::structs::Config,
+    runner::output::{output_url, RunnerOutput},
+    utils,
+};
+
+use super::{
+    observer::{Finding, InitialResponse, LearnStats, ScanObserver, TargetInfo},
+    scanner::ScanResults,
+};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS scans (
+    id INTEGER PRIMARY KEY,
+    started_at INTEGER NOT NULL,
+    finished_at INTEGER,
+    version TEXT NOT NULL,
+    config TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS targets (
+    id INTEGER PRIMARY KEY,
+    scan_id INTEGER NOT NULL REFERENCES scans(id),
+    method TEXT NOT NULL,
+    url TEXT NOT NULL,
+    host TEXT NOT NULL,
+    injection_place TEXT NOT NULL,
+    status INTEGER,
+    size INTEGER,
+    max INTEGER,
+    stable_body INTEGER,
+    stable_reflections INTEGER,
+    diffs INTEGER,
+    failed_requests INTEGER,
+    error TEXT,
+    started_at INTEGER NOT NULL,
+    finished_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS parameters (
+    id INTEGER PRIMARY KEY,
+    target_id INTEGER NOT NULL REFERENCES targets(id),
+    name TEXT NOT NULL,
+    value TEXT,
+    reason_kind TEXT NOT NULL,
+    status INTEGER NOT NULL,
+    size INTEGER NOT NULL,
+    diffs TEXT NOT NULL,
+    found_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS targets_host ON targets(host);
+CREATE INDEX IF NOT EXISTS parameters_name ON parameters(name);
+";
+
+/// unix time in seconds
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|x| x.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+fn host(url: &str) -> String {
+    Url::parse(&url.replace("%s", ""))
+        .ok()
+        .and_then(|x| x.host_str().map(|x| x.to_string()))
+        .unwrap_or_default()
+}
+
+/// the sqlite database with the results of all the scans (--db)
+pub struct Database {
+    connection: Connection,
+}
+
+/// a found parameter from x8 db query --host
+#[derive(Debug, Clone)]
+pub struct ParameterRecord {
+    pub method: String,
+    pub url: String,
+    pub name: String,
+    pub value: Option<String>,
+    pub reason_kind: String,
+
+    /// the amount of scans the parameter was found in
+    pub scans: usize,
+
+    /// YYYY-MM-DD HH:MM:SS in UTC
+    pub last_seen: String,
+}
+
+/// an url:method pair from x8 db query --param
+#[derive(Debug, Clone)]
+pub struct EndpointRecord {
+    pub method: String,
+    pub url: String,
+    pub scans: usize,
+    pub last_seen: String,
+}
+
+impl Database {
+    pub fn open(filename: &str) -> Result<Self, Box<dyn Error>> {
+        let connection = Connection::open(filename)?;
+        connection.execute_batch(SCHEMA)?;
+
+        Ok(Self { connection })
+    }
+
+    /// opens the existing database without creating or changing the schema
+    pub fn open_read_only(filename: &str) -> Result<Self, Box<dyn Error>> {
+        let connection = match Connection::open_with_flags(filename, OpenFlags::SQLITE_OPEN_READ_ONLY) {
+            Ok(val) => val,
+            Err(err) => Err(format!("Unable to open {}: {}", filename, err))?,
+        };
+
+        Ok(Self { connection })
+    }
+
+    /// creates a new scan and returns its id
+    pub fn add_scan(&self, config: &Config) -> Result<i64, Box<dyn Error>> {
+        self.connection.execute(
+            "INSERT INTO scans (started_at, version, config) VALUES (?1, ?2, ?3)",
+            params![now(), env!("CARGO_PKG_VERSION"), config_json(config)],
+        )?;
+
+        Ok(self.connection.last_insert_rowid())
+    }
+
+    pub fn finish_scan(&self, scan_id: i64) -> Result<(), Box<dyn Error>> {
+        self.connection.execute(
+            "UPDATE scans SET finished_at = ?1 WHERE id = ?2",
+            params![now(), scan_id],
+        )?;
+
+        Ok(())
+    }
+
+    /// saves the checked url:method pair together with its found parameters
+    pub fn add_target(
+        &mut self,
+        scan_id: i64,
+        target: &TargetInfo,
+        record: &TargetRecord,
+        result: Result<&RunnerOutput, &str>,
+    ) -> Result<(), Box<dyn Error>> {
+        let transaction = self.connection.transaction()?;
+
+        let (url, status, size, failed_requests, error) = match result {
+            Ok(output) => (
+                output.url.clone(),
+                Some(output.status),
+                Some(output.size as i64),
+                Some(output.failed_requests as i64),
+                None,
+            ),
+            Err(err) => (output_url(&target.url, target.injection_place), None, None, None, Some(err)),
+        };
+
+        let learn = record.learn.as_ref();
+
+        transaction.execute(
+            "INSERT INTO targets (scan_id, method, url, host, injection_place, status, size, max, stable_body,
+                stable_reflections, diffs, failed_requests, error, started_at, finished_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+            params![
+                scan_id,
+                target.method,
+                url,
+                host(&url),
+                format!("{:?}", target.injection_place),
+                status,
+                size,
+                learn.map(|x| x.max as i64),
+                learn.map(|x| x.stable_body),
+                learn.map(|x| x.stable_reflections),
+                learn.map(|x| x.diffs as i64),
+                failed_requests,
+                error,
+                record.started_at,
+                now(),
+            ],
+        )?;
+
+        let target_id = transaction.last_insert_rowid();
+
+        if let Ok(output) = result {
+            for parameter in output.found_params.iter() {
+                transaction.execute(
+                    "INSERT INTO parameters (target_id, name, value, reason_kind, status, size, diffs, found_at)
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    params![
+                        target_id,
+                        parameter.name,
+                        parameter.value,
+                        format!("{:?}", parameter.reason_kind),
+                        parameter.status,
+                        parameter.size as i64,
+                        parameter.diffs,
+                        record.found_at.get(&parameter.name).copied().unwrap_or_else(now),
+                    ],
+                )?;
+            }
+        }
+
+        transaction.commit()?;
+
+        Ok(())
+    }
+
+    /// parameters found on the host. The subdomains are included as well
+    pub fn parameters_by_host(&self, host: &str) -> Result<Vec<ParameterRecord>, Box<dyn Error>> {
+        let mut statement = self.connection.prepare(
+            "SELECT t.method, t.url, p.name, p.value, p.reason_kind, COUNT(DISTINCT t.scan_id),
+                datetime(MAX(p.found_at), 'unixepoch')
+            FROM parameters p JOIN targets t ON p.target_id = t.id
+            WHERE t.host = ?1 OR t.host LIKE '%.' || ?2 ESCAPE '\\'
+            GROUP BY t.method, t.url, p.name, p.value, p.reason_kind
+            ORDER BY t.url, t.method, p.name",
+        )?;
+
+        let records = statement
+            .query_map(params![host, escape_like(host)], |row| {
+                Ok(ParameterRecord {
+                    method: row.get(0)?,
+                    url: row.get(1)?,
+                    name: row.get(2)?,
+                    value: row.get(3)?,
+                    reason_kind: row.get(4)?,
+                    scans: row.get::<_, i64>(5)? as usize,
+                    last_seen: row.get(6)?,
+                })
+            })?
+            .collect::<Result<Vec<ParameterRecord>, rusqlite::Error>>()?;
+
+        Ok(records)
+    }
+
+    /// url:method pairs where the parameter was found
+    pub fn endpoints_by_parameter(&self, name: &str) -> Result<Vec<EndpointRecord>, Box<dyn Error>> {
+        let mut statement = self.connection.prepare(
+            "SELECT t.method, t.url, COUNT(DISTINCT t.scan_id), datetime(MAX(p.found_at), 'unixepoch')
+            FROM parameters p JOIN targets t ON p.target_id = t.id
+            WHERE p.name = ?1
+            GROUP BY t.method, t.url
+            ORDER BY t.url, t.method",
+        )?;
+
+        let records = statement
+            .query_map(params![name], |row| {
+                Ok(EndpointRecord {
+                    method: row.get(0)?,
+                    url: row.get(1)?,
+                    scans: row.get::<_, i64>(2)? as usize,
+                    last_seen: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<EndpointRecord>, rusqlite::Error>>()?;
+
+        Ok(records)
+    }
+}
+
+/// % and _ within the host would match other hosts otherwise
+fn escape_like(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+/// options that affect the results of the scan
+fn config_json(config: &Config) -> String {
+    json!({
+        "args": redact_args(std::env::args().collect()),
+        "urls": config.urls.iter().map(|x| redact_url(x)).collect::<Vec<String>>(),
+        "methods": config.methods,
+        "headers": config
+            .custom_headers
+            .iter()
+            .map(|(k, _)| (k.as_str(), REDACTED))
+            .collect::<Vec<(&str, &str)>>(),
+        "wordlists": config
+            .wordlists
+            .iter()
+            .map(|x| match &x.place {
+                Some(place) => format!("{}:{}", place, x.filename),
+                None => x.filename.clone(),
+            })
+            .collect::<Vec<String>>(),
+        "max": config.max,
+        "concurrency": config.concurrency,
+        "workers": config.workers,
+        "learn_requests_count": config.learn_requests_count,
+        "recursion_depth": config.recursion_depth,
+        "verify": config.verify,
+        "reflected_only": config.reflected_only,
+        "strict": config.strict,
+        "headers_discovery": config.headers_discovery,
+        "disable_custom_parameters": config.disable_custom_parameters,
+        "follow_redirects": config.follow_redirects,
+        "http_version": config.http_version.map(|x| format!("{:?}", x)),
+        "proxy": redact_url(&config.proxy),
+    })
+    .to_string()
+}
+
+/// header values, query values, bodies and proxy credentials usually contain secrets, so they aren't stored
+const REDACTED: &str = "[redacted]";
+
+const PROXY_ARGS: [&str; 3] = ["-x", "--proxy", "--replay-proxy"];
+const URL_ARGS: [&str; 2] = ["-u", "--url"];
+const BODY_ARGS: [&str; 2] = ["-b", "--body"];
+
+/// replaces secrets within the values of -H, -u, -b and proxy arguments
+pub(super) fn redact_args(args: Vec<String>) -> Vec<String> {
+    let mut option = String::new();
+
+    args.into_iter()
+        .map(|arg| {
+            if arg.starts_with('-') {
+                option = arg.clone();
+
+                match arg.split_once('=') {
+                    Some((k, v)) if k.starts_with("--") => format!("{}={}", k, redact_value(k, v)),
+                    _ => match arg.strip_prefix("-H") {
+                        Some(header) if !header.is_empty() => format!("-H{}", redact_header(header)),
+                        _ => arg,
+                    },
+                }
+            } else {
+                let value = redact_value(&option, &arg);
+
+                // -H and -u accept multiple values
+                if option != "-H" && !URL_ARGS.contains(&option.as_str()) {
+                    option.clear();
+                }
+
+                value
+            }
+        })
+        .collect()
+}
+
+fn redact_value(option: &str, value: &str) -> String {
+    if option == "-H" {
+        redact_header(value)
+    } else if PROXY_ARGS.contains(&option) || URL_ARGS.contains(&option) {
+        redact_url(value)
+    } else if BODY_ARGS.contains(&option) {
+        REDACTED.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+fn redact_header(header: &str) -> String {
+    match header.split_once(':') {
+        Some((k, _)) => format!("{}: {}", k.trim(), REDACTED),
+        None => header.to_string(),
+    }
+}
+
+/// removes credentials and replaces query values. Parameter names and the %s injection point are kept
+fn redact_url(url: &str) -> String {
+    let url = match Url::parse(url) {
+        Ok(mut val) if !val.username().is_empty() || val.password().is_some() => {
+            val.set_username("").ok();
+            val.set_password(None).ok();
+            val.to_string()
+        }
+        _ => url.to_string(),
+    };
+
+    match url.split_once('?') {
+        Some((base, query)) => format!(
+            "{}?{}",
+            base,
+            query
+                .split('&')
+                .map(|x| match x.split_once('=') {
+                    Some((k, _)) => format!("{}={}", k, REDACTED),
+                    None => x.to_string(),
+                })
+                .collect::<Vec<String>>()
+                .join("&")
+        ),
+        None => url,
+    }
+}
+
+/// what is known about the url:method pair before it's finished
+#[derive(Debug, Clone)]
+pub struct TargetRecord {
+    started_at: i64,
+
+    /// stats of the last learning phase (the recursion starts a new one)
+    learn: Option<LearnStats>,
+
+    /// parameter name -> the time it was found
+    found_at: HashMap<String, i64>,
+}
+
+impl Default for TargetRecord {
+    fn default() -> Self {
+        Self {
+            started_at: now(),
+            learn: None,
+            found_at: HashMap::new(),
+        }
+    }
+}
+
+/// records the scan to the database
+pub struct DbObserver {
+    db: Mutex<Database>,
+    scan_id: i64,
+
+    /// "method url" -> the record of the url:method pair being checked
+    targets: Mutex<HashMap<String, TargetRecord>>,
+}
+
+impl DbObserver {
+    pub fn new(config: &Config) -> Result<Self, Box<dyn Error>> {
+        let db = Database::open(&config.db)?;
+        let scan_id = db.add_scan(config)?;
+
+        Ok(Self {
+            db: Mutex::new(db),
+            scan_id,
+            targets: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn key(target: &TargetInfo) -> String {
+        format!("{} {} {:016x}", target.method, target.url, target.target_hash)
+    }
+
+    fn add_target(&self, target: &TargetInfo, result: Result<&RunnerOutput, &str>) {
+        let record = self.targets.lock().remove(&Self::key(target)).unwrap_or_default();
+
+        if let Err(err) = self.db.lock().add_target(self.scan_id, target, &record, result) {
+            utils::error(format!("Unable to save the results to the database: {}", err), None, None, None);
+        }
+    }
+}
+
+impl ScanObserver for DbObserver {
+    fn on_target_start(&self, target: &TargetInfo, _initial: &InitialResponse) {
+        self.targets.lock().entry(Self::key(target)).or_default();
+    }
+
+    fn on_learn_complete(&self, target: &TargetInfo, stats: &LearnStats) {
+        self.targets.lock().entry(Self::key(target)).or_default().learn = Some(stats.clone());
+    }
+
+    fn on_parameter_found(&self, target: &TargetInfo, finding: &Finding) {
+        self.targets
+            .lock()
+            .entry(Self::key(target))
+            .or_default()
+            .found_at
+            .entry(finding.parameter.name.clone())
+            .or_insert_with(now);
+    }
+
+    fn on_target_done(&self, target: &TargetInfo, output: &RunnerOutput) {
+        self.add_target(target, Ok(output));
+    }
+
+    fn on_target_error(&self, target: &TargetInfo, error: &str) {
+        self.add_target(target, Err(error));
+    }
+
+    fn on_scan_done(&self, _results: &ScanResults) {
+        if let Err(err) = self.db.lock().finish_scan(self.scan_id) {
+            utils::error(format!("Unable to save the results to the database: {}", err), None, None, None);
+        }
+    }
+}
+
+/// x8 db query: prints parameters found on the host or endpoints where the parameter was found
+pub fn write_query(filename: &str, host: Option<&str>, parameter: Option<&str>) -> Result<(), Box<dyn Error>> {
+    if !std::path::Path::new(filename).exists() {
+        Err(format!("{} doesn't exist", filename))?;
+    }
+
+    let db = Database::open_read_only(filename)?;
+
+    let mut stdout = io::stdout();
+
+    if let Some(host) = host {
+        for record in db.parameters_by_host(host)? {
+            let name = match &record.value {
+                Some(value) => format!("{}={}", record.name, value),
+                None => record.name.clone(),
+            };
+
+            writeln!(
+                stdout,
+                "{} {} % {} ({}) [scans: {}, last seen: {}]",
+                record.method.blue(),
+                record.url,
+                name.green(),
+                record.reason_kind,
+                record.scans,
+                record.last_seen
+            )
+            .ok();
+        }
+    }
+
+    if let Some(parameter) = parameter {
+        for record in db.endpoints_by_parameter(parameter)? {
+            writeln!(
+                stdout,
+                "{} {} [scans: {}, last seen: {}]",
+                record.method.blue(),
+                record.url,
+                record.scans,
+                record.last_seen
+            )
+            .ok();
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,9 @@
+pub mod builder;
+pub mod db;
+pub mod observer;
+#[allow(clippy::module_inception)]
+pub mod scanner;
+pub mod terminal;
+
+#[allow(clippy::module_inception)]
+mod tests;
@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    network::utils::InjectionPlace,
+    runner::{output::RunnerOutput, utils::FoundParameter},
+};
+
+use super::scanner::ScanResults;
+
+/// receives the events of the scan as they happen.
+/// Every method does nothing by default, so only the needed ones can be implemented
+pub trait ScanObserver: Send + Sync {
+    /// the initial response was received.
+    /// Called for every run of the url:method pair (the recursion starts a new one)
+    fn on_target_start(&self, _target: &TargetInfo, _initial: &InitialResponse) {}
+
+    /// a learning request was made. done is 0 before the first one
+    fn on_learn_progress(&self, _target: &TargetInfo, _done: usize, _total: usize) {}
+
+    /// the page's behavior was learned and the checking of parameters begins
+    fn on_learn_complete(&self, _target: &TargetInfo, _stats: &LearnStats) {}
+
+    /// a chunk of parameters was checked. done is 0 before the first one
+    fn on_chunk_done(&self, _target: &TargetInfo, _done: usize, _total: usize) {}
+
+    /// a parameter was found. Parameters can be filtered out later (for example, via --verify),
+    /// so on_target_done contains the final list
+    fn on_parameter_found(&self, _target: &TargetInfo, _finding: &Finding) {}
+
+    /// intermediate information like the amount of parameters per request
+    fn on_info(&self, _target: &TargetInfo, _kind: &str, _msg: &str) {}
+
+    /// non critical errors
+    fn on_warning(&self, _target: &TargetInfo, _msg: &str) {}
+
+    fn on_target_done(&self, _target: &TargetInfo, _output: &RunnerOutput) {}
+
+    /// the url:method pair wasn't checked
+    fn on_target_error(&self, _target: &TargetInfo, _error: &str) {}
+
+    /// every url:method pair was checked
+    fn on_scan_done(&self, _results: &ScanResults) {}
+}
+
+/// ignores every event
+pub struct NoopObserver;
+
+impl ScanObserver for NoopObserver {}
+
+/// passes every event to each of the observers
+pub struct Observers<'a>(pub Vec<&'a dyn ScanObserver>);
+
+impl ScanObserver for Observers<'_> {
+    fn on_target_start(&self, target: &TargetInfo, initial: &InitialResponse) {
+        self.0.iter().for_each(|x| x.on_target_start(target, initial));
+    }
+
+    fn on_learn_progress(&self, target: &TargetInfo, done: usize, total: usize) {
+        self.0.iter().for_each(|x| x.on_learn_progress(target, done, total));
+    }
+
+    fn on_learn_complete(&self, target: &TargetInfo, stats: &LearnStats) {
+        self.0.iter().for_each(|x| x.on_learn_complete(target, stats));
+    }
+
+    fn on_chunk_done(&self, target: &TargetInfo, done: usize, total: usize) {
+        self.0.iter().for_each(|x| x.on_chunk_done(target, done, total));
+    }
+
+    fn on_parameter_found(&self, target: &TargetInfo, finding: &Finding) {
+        self.0.iter().for_each(|x| x.on_parameter_found(target, finding));
+    }
+
+    fn on_info(&self, target: &TargetInfo, kind: &str, msg: &str) {
+        self.0.iter().for_each(|x| x.on_info(target, kind, msg));
+    }
+
+    fn on_warning(&self, target: &TargetInfo, msg: &str) {
+        self.0.iter().for_each(|x| x.on_warning(target, msg));
+    }
+
+    fn on_target_done(&self, target: &TargetInfo, output: &RunnerOutput) {
+        self.0.iter().for_each(|x| x.on_target_done(target, output));
+    }
+
+    fn on_target_error(&self, target: &TargetInfo, error: &str) {
+        self.0.iter().for_each(|x| x.on_target_error(target, error));
+    }
+
+    fn on_scan_done(&self, results: &ScanResults) {
+        self.0.iter().for_each(|x| x.on_scan_done(results));
+    }
+}
+
+/// the url:method pair being checked
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetInfo {
+    /// unique id of the target set to distinguish output between different urls
+    pub id: usize,
+    pub method: String,
+
+    /// url with the injection point
+    pub url: String,
+
+    /// see RequestDefaults::target_hash()
+    pub target_hash: u64,
+
+    pub injection_place: InjectionPlace,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InitialResponse {
+    pub status: u16,
+    pub size: usize,
+
+    /// how many times a random value is reflected
+    pub reflections: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LearnStats {
+    /// the amount of parameters per request
+    pub max: usize,
+
+    /// whether the body and reflections don't change between requests with random parameters
+    pub stable_body: bool,
+    pub stable_reflections: bool,
+
+    /// parts of the page that change on their own
+    pub diffs: usize,
+}
+
+/// a parameter found within a single response
+#[derive(Debug, Clone)]
+pub struct Finding<'a> {
+    pub parameter: &'a FoundParameter,
+
+    /// the initial response to compare with
+    pub initial_status: u16,
+    pub initial_size: usize,
+
+    /// the first new diff in case the parameter changes the page's body
+    pub diff: Option<&'a str>,
+}
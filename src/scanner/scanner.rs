@@ -0,0 +1,352 @@
+use std::{error::Error, fs, iter::FromIterator};
+
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{
+        structs::{Config, Target},
+        wordlist::Wordlists,
+    },
+    network::{
+        request::{Request, RequestDefaults},
+        utils::{Headers, InjectionPlace},
+    },
+    runner::{
+        baseline::{Baseline, BaselineStatus},
+        output::RunnerOutput,
+        runner::Runner,
+        state::StateFile,
+        utils::{Parameters, ReasonKind},
+    },
+    utils::order_targets,
+};
+
+use super::{
+    builder::ScannerBuilder,
+    observer::{NoopObserver, ScanObserver, TargetInfo},
+};
+
+/// checks targets from the config with the parameters from the wordlists.
+/// Doesn't print anything by itself (see ScanObserver), so it can be used as a library:
+///
+/// ```no_run
+/// # async fn scan() -> Result<(), Box<dyn std::error::Error>> {
+/// let results = x8::scanner::scanner::Scanner::builder()
+///     .target("https://example.com/")
+///     .wordlist(vec!["debug", "admin"])
+///     .build()?
+///     .run()
+///     .await;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Scanner {
+    config: Config,
+    wordlists: Wordlists,
+
+    /// stores the progress in case --state or --resume is used
+    state: Option<StateFile>,
+
+    /// found parameters of the previous scan in case --baseline is used
+    baseline: Option<Baseline>,
+}
+
+/// results of the whole scan
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScanResults {
+    /// one output per every checked url:method pair
+    pub outputs: Vec<RunnerOutput>,
+
+    /// url:method pairs that weren't checked
+    pub errors: Vec<TargetError>,
+}
+
+impl ScanResults {
+    /// the amount of parameters that weren't found within the baseline
+    pub fn new_params(&self) -> usize {
+        self.outputs
+            .iter()
+            .flat_map(|x| x.found_params.iter())
+            .filter(|x| x.baseline == Some(BaselineStatus::New))
+            .count()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetError {
+    pub method: String,
+    pub url: String,
+    pub error: String,
+}
+
+impl Scanner {
+    pub fn builder() -> ScannerBuilder {
+        ScannerBuilder::default()
+    }
+
+    /// creates a scanner from the already parsed config and wordlists
+    pub fn new(config: Config, wordlists: Wordlists) -> Result<Self, Box<dyn Error>> {
+        if !config.save_responses.is_empty() {
+            fs::create_dir_all(&config.save_responses)?;
+        }
+
+        // the progress of the scan is saved in case --state or --resume is used
+        let state = if !config.state_file.is_empty() {
+            Some(StateFile::new(&config.state_file, config.resume)?)
+        } else {
+            None
+        };
+
+        let baseline = if !config.baseline.is_empty() {
+            Some(Baseline::read(&config.baseline)?)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            config,
+            wordlists,
+            state,
+            baseline,
+        })
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn wordlists(&self) -> &Wordlists {
+        &self.wordlists
+    }
+
+    /// the amount of target sets checked in parallel
+    pub fn workers(&self) -> usize {
+        // -W 0 is a special option to run everything in parallel
+        if self.config.workers == 0 {
+            self.config.urls.len() * self.config.methods.len()
+        } else {
+            self.config.workers
+        }
+    }
+
+    /// checks every target and returns the results once all of them are finished
+    pub async fn run(&self) -> ScanResults {
+        self.run_with(&NoopObserver).await
+    }
+
+    /// the same as run() but passes the events of the scan to the observer
+    pub async fn run_with(&self, observer: &dyn ScanObserver) -> ScanResults {
+        let target_sets = order_targets(&self.config.targets, self.config.one_worker_per_host);
+
+        let results = futures::stream::iter(target_sets.into_iter().enumerate().map(
+            |(id, target_set)| async move {
+                let mut results = Vec::with_capacity(target_set.len());
+
+                // ids start from 1 like the progress bars from init_progress()
+                for target in target_set.iter() {
+                    results.push(
+                        self.scan(target, observer, id + 1)
+                            .await
+                            .map_err(|err| TargetError {
+                                method: target.method.clone(),
+                                url: target.url.clone(),
+                                error: err.to_string(),
+                            }),
+                    );
+                }
+
+                results
+            },
+        ))
+        .buffer_unordered(self.workers().max(1))
+        .collect::<Vec<Vec<Result<RunnerOutput, TargetError>>>>()
+        .await;
+
+        let mut scan_results = ScanResults::default();
+
+        for result in results.into_iter().flatten() {
+            match result {
+                Ok(val) => scan_results.outputs.push(val),
+                Err(err) => scan_results.errors.push(err),
+            }
+        }
+
+        observer.on_scan_done(&scan_results);
+
+        scan_results
+    }
+
+    /// checks a single url:method pair
+    pub async fn scan(
+        &self,
+        target: &Target,
+        observer: &dyn ScanObserver,
+        id: usize,
+    ) -> Result<RunnerOutput, Box<dyn Error>> {
+        let mut target_info = TargetInfo {
+            id,
+            method: target.method.clone(),
+            url: target.url.clone(),
+            target_hash: 0,
+            injection_place: InjectionPlace::default(),
+        };
+
+        let result = match RequestDefaults::from_config(&self.config, target) {
+            Ok(mut request_defaults) => {
+                target_info.url = request_defaults.url_without_default_port();
+                target_info.target_hash = request_defaults.target_hash();
+                target_info.injection_place = request_defaults.injection_place;
+                self.check(&mut request_defaults, &target_info, observer)
+                    .await
+                    .map(|mut runner_output| {
+                        if let Some(baseline) = &self.baseline {
+                            baseline.compare(&mut runner_output, self.config.only_new);
+                            // --only-new could remove some parameters
+                            runner_output.prepare(&self.config, &request_defaults);
+                        }
+                        runner_output
+                    })
+            }
+            Err(err) => Err(err),
+        };
+
+        match &result {
+            Ok(val) => observer.on_target_done(&target_info, val),
+            Err(err) => observer.on_target_error(&target_info, &err.to_string()),
+        }
+
+        result
+    }
+
+    async fn check(
+        &self,
+        request_defaults: &mut RequestDefaults,
+        target: &TargetInfo,
+        observer: &dyn ScanObserver,
+    ) -> Result<RunnerOutput, Box<dyn Error>> {
+        let config = &self.config;
+        let state = self.state.as_ref();
+
+        // each target should have each own list of parameters (we're changing this list through the run)
+        let mut params = self.wordlists.for_request(request_defaults);
+
+        // documented parameters are already sent within every request
+        params.retain(|x| !request_defaults.parameters.contains_key(x));
+
+        // the url:method pair was checked before the scan was interrupted
+        if let Some(mut runner_output) = state.and_then(|x| x.finished(request_defaults)) {
+            runner_output.prepare(config, request_defaults);
+            return Ok(runner_output);
+        }
+
+        // get cookies
+        Request::new(request_defaults, Vec::new()).send().await?;
+
+        let runner_output = run(
+            config,
+            request_defaults,
+            &mut params,
+            observer,
+            target,
+            state,
+            self.baseline.as_ref(),
+        )
+        .await?;
+
+        if let Some(state) = state {
+            if let Err(err) = state.save_finished(request_defaults, &runner_output) {
+                observer.on_warning(target, &format!("unable to save the state: {}", err));
+            }
+        }
+
+        Ok(runner_output)
+    }
+}
+
+/// runs the runner and repeats it with the found parameters in case --recursion-depth is used
+async fn run(
+    config: &Config,
+    request_defaults: &mut RequestDefaults,
+    params: &mut Vec<String>,
+    observer: &dyn ScanObserver,
+    target: &TargetInfo,
+    state: Option<&StateFile>,
+    baseline: Option<&Baseline>,
+) -> Result<RunnerOutput, Box<dyn Error>> {
+    let mut runner_output = Runner::new(config, request_defaults, observer, target.clone(), state, baseline)
+        .await?
+        .run(params)
+        .await?;
+
+    // the whole block related to the recursive searching
+    if !runner_output.found_params.is_empty() {
+        for depth in 1..config.recursion_depth + 1 {
+            // remove already found parameters from the list to prevent duplicates
+            params.retain(|x| !runner_output.found_params.contains_name(x));
+
+            // custom parameters work badly with recursion enabled
+            request_defaults.disable_custom_parameters = true;
+
+            // so we are keeping parameters that don't change pages' code
+            // or change it to 200
+            // we cant simply overwrite request_defaults.parameters because there's user-supplied parameters as well.
+            request_defaults.parameters.append(&mut Vec::from_iter(
+                runner_output
+                    .found_params
+                    .iter()
+                    .filter(|x| {
+                        !request_defaults.parameters.contains_key(&x.name)
+                            && (x.reason_kind != ReasonKind::Code || x.status == 200)
+                    })
+                    .map(|x| x.get()),
+            ));
+
+            observer.on_info(
+                target,
+                "recursion",
+                &format!(
+                    "({}) repeating with {}",
+                    depth,
+                    request_defaults
+                        .parameters
+                        .iter()
+                        .map(|x| x.0.as_str())
+                        .collect::<Vec<&str>>()
+                        .join(", ")
+                ),
+            );
+
+            let mut new_found_params = Runner::new(config, request_defaults, observer, target.clone(), state, baseline)
+                .await?
+                .run(params)
+                .await?
+                .found_params;
+
+            // no new params where found - just quit the loop
+            if !new_found_params
+                .iter()
+                .any(|x| !runner_output.found_params.contains_name(&x.name))
+            {
+                break;
+            }
+
+            runner_output.found_params.append(&mut new_found_params);
+        }
+    }
+
+    // we probably changed request_defaults.parameters within the loop above
+    // so we are removing all of the added parameters in there
+    // leaving only user-supplied ones
+    // (to not cause double parameters in some output types)
+    request_defaults.parameters = request_defaults
+        .parameters
+        .iter()
+        .filter(|x| !runner_output.found_params.contains_name(&x.0))
+        .map(|x| x.to_owned())
+        .collect();
+
+    runner_output.prepare(config, request_defaults);
+
+    Ok(runner_output)
+}
@@ -0,0 +1,348 @@
+use std::{
+    error::Error,
+    fs::{File, OpenOptions},
+    io::{self, Write},
+};
+
+use colored::Colorize;
+use indicatif::{ProgressBar, ProgressStyle};
+use parking_lot::Mutex;
+
+use crate::{
+    config::structs::Config,
+    network::response::colored_code,
+    runner::{
+        baseline::BaselineStatus,
+        output::{output_url, OutputEvent, ParseOutputs, RunnerOutput, SCAN_OUTPUT_FORMATS},
+        table::{self, TABLE_OUTPUT_FORMATS},
+        utils::{fold_url, ReasonKind},
+    },
+    utils::{self, color_id, is_id_important, progress_style_check_requests, progress_style_learn_requests},
+    DEFAULT_PROGRESS_URL_MAX_LEN,
+};
+
+use super::{
+    observer::{Finding, InitialResponse, ScanObserver, TargetInfo},
+    scanner::ScanResults,
+};
+
+/// the command line output: progress bars, found parameters and messages.
+/// Also prints outputs of url:method pairs and writes them to the output file
+pub struct TerminalObserver<'a> {
+    config: &'a Config,
+
+    /// progress bars from init_progress(). The index is the id of the target set
+    progress_bars: Vec<ProgressBar>,
+
+    output_file: Option<Mutex<File>>,
+
+    /// whether the header of csv or markdown table is already printed
+    table_header_printed: Mutex<bool>,
+}
+
+impl<'a> TerminalObserver<'a> {
+    pub fn new(config: &'a Config, progress_bars: Vec<ProgressBar>) -> Result<Self, Box<dyn Error>> {
+        let output_file = if !config.output_file.is_empty() {
+            let file = OpenOptions::new()
+                .write(true)
+                .create(true)
+                .append(config.append)
+                .truncate(!config.append)
+                .open(&config.output_file)?;
+
+            Some(Mutex::new(file))
+        } else {
+            None
+        };
+
+        Ok(Self {
+            config,
+            progress_bars,
+            output_file,
+            table_header_printed: Mutex::new(false),
+        })
+    }
+
+    fn progress_bar(&self, id: usize) -> &ProgressBar {
+        &self.progress_bars[id]
+    }
+
+    /// in case progress bars are hidden -- the messages from progress_bar.println arent' displayed, so we need to use writeln instead
+    fn println(&self, id: usize, msg: String) {
+        if self.config.disable_progress_bar {
+            writeln!(io::stdout(), "{}", msg).ok();
+        } else {
+            self.progress_bar(id).println(msg);
+        }
+    }
+
+    fn write_to_file(&self, output: &str) -> Result<(), Box<dyn Error>> {
+        if let Some(file) = &self.output_file {
+            let mut file = file.lock();
+            file.write_all(output.as_bytes())?;
+            file.flush()?;
+        }
+
+        Ok(())
+    }
+
+    /// prints the jsonl event and writes it to the output file right away
+    fn write_event(&self, id: usize, event: &OutputEvent) {
+        let line = serde_json::to_string(event).unwrap();
+
+        if let Err(err) = self.write_to_file(&(line.clone() + "\n")) {
+            utils::error(err, None, Some(self.progress_bar(id)), Some(self.config));
+        }
+
+        self.println(id, line);
+    }
+
+    /// prints the rows and writes them to the output file.
+    /// The header is written only to empty files, so it isn't repeated with --append
+    fn write_table_rows(&self, id: usize, rows: &str) -> Result<(), Box<dyn Error>> {
+        let header = table::header(&self.config.output_format);
+
+        if let Some(file) = &self.output_file {
+            let mut file = file.lock();
+            if file.metadata()?.len() == 0 {
+                file.write_all(header.as_bytes())?;
+            }
+            file.write_all(rows.as_bytes())?;
+            file.flush()?;
+        }
+
+        let mut table_header_printed = self.table_header_printed.lock();
+        let output = if !*table_header_printed {
+            *table_header_printed = true;
+            header + rows
+        } else {
+            rows.to_string()
+        };
+
+        self.println(id, output.trim_end().to_string());
+
+        Ok(())
+    }
+
+    fn prepare_progress_bar(&self, target: &TargetInfo, sty: ProgressStyle, length: usize) {
+        let progress_bar = self.progress_bar(target.id);
+        progress_bar.reset();
+        progress_bar.set_prefix(self.make_progress_prefix(target));
+        progress_bar.set_style(sty);
+        progress_bar.set_length(length as u64);
+    }
+
+    fn make_progress_prefix(&self, target: &TargetInfo) -> String {
+        // to align all the progress bars
+        let id = if is_id_important(self.config) {
+            let mut id = target.id.to_string() + ":";
+            id += &" ".repeat(1 + self.config.urls.len().to_string().len() - id.to_string().len());
+            format!("{} ", id.replace(&target.id.to_string(), &color_id(target.id)))
+        } else {
+            String::new()
+        };
+
+        let mut method = target.method.clone();
+        method += &" ".repeat(
+            self.config
+                .methods
+                .iter()
+                .map(|x| x.len())
+                .max()
+                .unwrap_or_default()
+                .saturating_sub(method.len()),
+        );
+
+        format!(
+            "{}{} {}",
+            id,
+            method.blue(),
+            fold_url(&target.url, DEFAULT_PROGRESS_URL_MAX_LEN).green()
+        )
+    }
+}
+
+impl ScanObserver for TerminalObserver<'_> {
+    fn on_target_start(&self, target: &TargetInfo, initial: &InitialResponse) {
+        let id = if is_id_important(self.config) {
+            format!("[{}] ", color_id(target.id))
+        } else {
+            String::new()
+        };
+
+        self.println(
+            target.id,
+            format!(
+                "{}{} {} ({}) [{}] {{{}}}",
+                id,
+                target.method.blue(),
+                target.url.green(),
+                colored_code(initial.status),
+                initial.size.to_string().green(),
+                initial.reflections.to_string().magenta()
+            ),
+        );
+    }
+
+    fn on_learn_progress(&self, target: &TargetInfo, done: usize, total: usize) {
+        if done == 0 {
+            self.prepare_progress_bar(target, progress_style_learn_requests(self.config), total);
+        } else {
+            self.progress_bar(target.id).set_position(done as u64);
+        }
+    }
+
+    fn on_chunk_done(&self, target: &TargetInfo, done: usize, total: usize) {
+        if done == 0 {
+            self.prepare_progress_bar(target, progress_style_check_requests(self.config), total);
+        } else {
+            self.progress_bar(target.id).set_position(done as u64);
+        }
+    }
+
+    fn on_parameter_found(&self, target: &TargetInfo, finding: &Finding) {
+        if self.config.output_format == "jsonl" {
+            self.write_event(
+                target.id,
+                &OutputEvent::Finding {
+                    method: &target.method,
+                    url: output_url(&target.url, target.injection_place),
+                    injection_place: target.injection_place,
+                    parameter: finding.parameter,
+                    initial_status: finding.initial_status,
+                    initial_size: finding.initial_size,
+                },
+            );
+        }
+
+        if self.config.verbose == 0 {
+            return;
+        }
+
+        let id = if is_id_important(self.config) {
+            format!("{}) ", color_id(target.id))
+        } else {
+            String::new()
+        };
+
+        let parameter = finding.parameter;
+        let name = match &parameter.value {
+            Some(value) => format!("{}={}", parameter.name, value),
+            None => parameter.name.clone(),
+        };
+
+        let mut message = match parameter.reason_kind {
+            ReasonKind::Code => format!(
+                "{}{}: code {} -> {}",
+                id,
+                name,
+                colored_code(finding.initial_status),
+                colored_code(parameter.status),
+            ),
+            ReasonKind::Text => format!(
+                "{}{}: page {} -> {} ({})",
+                id,
+                name,
+                finding.initial_size,
+                parameter.size.to_string().bright_yellow(),
+                finding.diff.unwrap_or_default()
+            ),
+            ReasonKind::Reflected => format!("{}{}: {}", id, "reflects".bright_blue(), name),
+            ReasonKind::NotReflected => format!("{}{}: {}", id, "changes reflections".bright_cyan(), name),
+        };
+
+        if parameter.baseline == Some(BaselineStatus::New) {
+            message += &format!(" {}", "(new)".green());
+        }
+
+        if let Some(filename) = &parameter.saved_to {
+            message += &format!(" [saved to {}]", filename);
+        }
+
+        self.println(target.id, message);
+    }
+
+    fn on_info(&self, target: &TargetInfo, kind: &str, msg: &str) {
+        utils::info(self.config, target.id, self.progress_bar(target.id), kind, msg);
+    }
+
+    fn on_warning(&self, target: &TargetInfo, msg: &str) {
+        utils::info(self.config, target.id, self.progress_bar(target.id), "~", msg);
+    }
+
+    fn on_target_done(&self, target: &TargetInfo, output: &RunnerOutput) {
+        // json, sarif and html outputs are printed all together once the scan is finished
+        if SCAN_OUTPUT_FORMATS.contains(&self.config.output_format.as_str()) {
+            return;
+        }
+
+        if self.config.output_format == "jsonl" {
+            if !(self.config.remove_empty && output.found_params.is_empty()) {
+                self.write_event(target.id, &OutputEvent::Summary(output));
+            }
+            return;
+        }
+
+        if TABLE_OUTPUT_FORMATS.contains(&self.config.output_format.as_str()) {
+            if !output.found_params.is_empty() {
+                if let Err(err) = self.write_table_rows(target.id, &output.parse(self.config)) {
+                    utils::error(err, None, Some(self.progress_bar(target.id)), Some(self.config));
+                }
+            }
+            return;
+        }
+
+        let parsed = output.parse(self.config);
+
+        if !(self.config.remove_empty && output.found_params.is_empty()) {
+            let stripped = strip_ansi_escapes::strip((parsed.normal().clear().to_string() + "\n").as_bytes()).unwrap();
+
+            if let Err(err) = self.write_to_file(&String::from_utf8_lossy(&stripped)) {
+                utils::error(err, None, Some(self.progress_bar(target.id)), Some(self.config));
+            }
+        }
+
+        let msg = if self.config.verbose > 0 {
+            format!("\n{}\n\n", parsed)
+        } else {
+            parsed
+        };
+
+        self.println(target.id, msg);
+    }
+
+    fn on_target_error(&self, target: &TargetInfo, error: &str) {
+        utils::error(error, Some(&target.url), Some(self.progress_bar(target.id)), Some(self.config));
+
+        if self.config.output_format == "jsonl" {
+            self.write_event(
+                target.id,
+                &OutputEvent::Error {
+                    method: &target.method,
+                    url: &target.url,
+                    error,
+                },
+            );
+        }
+    }
+
+    fn on_scan_done(&self, results: &ScanResults) {
+        if !SCAN_OUTPUT_FORMATS.contains(&self.config.output_format.as_str()) || results.outputs.is_empty() {
+            return;
+        }
+
+        let output = results
+            .outputs
+            .iter()
+            .filter(|x| !(self.config.remove_empty && x.found_params.is_empty()))
+            .cloned()
+            .collect::<Vec<RunnerOutput>>()
+            .parse_output(self.config);
+
+        if let Err(err) = self.write_to_file(&output) {
+            utils::error(err, None, None, None);
+        }
+
+        write!(io::stdout(), "\n{}", output).ok();
+    }
+}
@@ -10,7 +10,7 @@ use linked_hash_map::LinkedHashMap;
 use rand::Rng;
 use url::Url;
 
-use crate::{config::structs::Config, RANDOM_CHARSET};
+use crate::{config::structs::{Config, Target}, RANDOM_CHARSET};
 
 pub fn progress_style_learn_requests(config: &Config) -> ProgressStyle {
     if config.disable_colors {
@@ -71,21 +71,21 @@ pub fn info<S: Into<String>, T: std::fmt::Display>(
 
 /// prints errors. Progress_bar may be null in case the error happened too early (before requests)
 pub fn error<T: std::fmt::Display>(msg: T, url: Option<&str>, progress_bar: Option<&ProgressBar>, config: Option<&Config>) {
-    let message = if url.is_none() {
-        format!("{} {}", "[#]".red(), msg)
-    } else {
-        format!("{} [{}] {}", "[#]".red(), url.unwrap(), msg)
+    let message = match url {
+        Some(url) => format!("{} [{}] {}", "[#]".red(), url, msg),
+        None => format!("{} {}", "[#]".red(), msg),
     };
 
-    if progress_bar.is_none() || (config.is_some() && config.unwrap().disable_progress_bar) {
-        writeln!(io::stdout(), "{}", message).ok();
-    } else {
-        progress_bar.unwrap().println(message);
+    match progress_bar {
+        Some(progress_bar) if !config.is_some_and(|x| x.disable_progress_bar) => progress_bar.println(message),
+        _ => {
+            writeln!(io::stdout(), "{}", message).ok();
+        }
     }
 }
 
-/// initialize progress bars for every url set
-pub fn init_progress(config: &Config) -> Vec<(ProgressBar, Vec<String>)> {
+/// initialize progress bars for every target set. The index of a progress bar is the id of the target set
+pub fn init_progress(config: &Config) -> Vec<ProgressBar> {
     let mut urls_to_progress = Vec::new();
     let m = MultiProgress::new();
 
@@ -94,18 +94,14 @@ pub fn init_progress(config: &Config) -> Vec<(ProgressBar, Vec<String>)> {
     let empty_sty = ProgressStyle::with_template(" ").unwrap();
     empty_line.set_style(empty_sty.clone());
     empty_line.inc(1);
-    urls_to_progress.push((empty_line, vec![String::new()]));
+    urls_to_progress.push(empty_line);
 
-    // in case --one-worker-per-host option is provided -- each url set contains urls with one host
-    // otherwise it's just url sets with one url
-    let urls = if config.one_worker_per_host {
-        order_urls(&config.urls)
-    } else {
-        config.urls.iter().map(|x| vec![x.to_owned()]).collect()
-    };
+    // in case --one-worker-per-host option is provided -- each target set contains targets with one host
+    // otherwise it's just target sets with one url (and every method for this url)
+    let target_sets = order_targets(&config.targets, config.one_worker_per_host);
 
     // append progress bars one after another and push them to urls_to_progress
-    for url_set in urls {
+    for _ in target_sets {
         let pb = m.insert_from_back(
             0,
             ProgressBar::new(0)
@@ -117,7 +113,7 @@ pub fn init_progress(config: &Config) -> Vec<(ProgressBar, Vec<String>)> {
             pb.set_draw_target(ProgressDrawTarget::hidden());
         }
 
-        urls_to_progress.push((pb, url_set));
+        urls_to_progress.push(pb);
     }
 
     urls_to_progress
@@ -135,7 +131,7 @@ where
 /// read parameters from stdin
 pub fn read_stdin_lines() -> Vec<String> {
     let stdin = io::stdin();
-    stdin.lock().lines().filter_map(|x| x.ok()).collect()
+    stdin.lock().lines().map_while(Result::ok).collect()
 }
 
 /// generate random word of RANDOM_CHARSET chars
@@ -148,48 +144,63 @@ pub fn random_line(size: usize) -> String {
         .collect()
 }
 
+/// FNV-1a hash of the newline separated parts. Unlike DefaultHasher, it doesn't change between Rust versions
+pub fn fnv_hash<'a, I: IntoIterator<Item = &'a str>>(parts: I) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+
+    for byte in parts.into_iter().flat_map(|x| x.bytes().chain(std::iter::once(b'\n'))) {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+
+    hash
+}
+
 /// returns colored id when > 1 url is being tested in the same time
 pub fn color_id(id: usize) -> String {
-    if id % 7 == 0 {
+    if id.is_multiple_of(7) {
         id.to_string().white()
-    } else if id % 6 == 0 {
+    } else if id.is_multiple_of(6) {
         id.to_string().bright_red()
-    } else if id % 5 == 0 {
+    } else if id.is_multiple_of(5) {
         id.to_string().bright_cyan()
-    } else if id % 4 == 0 {
+    } else if id.is_multiple_of(4) {
         id.to_string().bright_blue()
-    } else if id % 3 == 0 {
+    } else if id.is_multiple_of(3) {
         id.to_string().yellow()
-    } else if id % 2 == 0 {
+    } else if id.is_multiple_of(2) {
         id.to_string().bright_green()
     } else {
         id.to_string().magenta()
     }.to_string()
 }
 
-/// moves urls with different hosts to different vectors
-pub fn order_urls(urls: &[String]) -> Vec<Vec<String>> {
+/// moves targets with different urls (or hosts in case by_host is true) to different vectors
+pub fn order_targets(targets: &[Target], by_host: bool) -> Vec<Vec<Target>> {
     // LinkedHashMap instead of hashmap for preserving the order
-    // LinkedHashMap<HOST, Vec<URL>>
-    let mut sorted_urls: LinkedHashMap<String, Vec<String>> = LinkedHashMap::new();
-    let mut ordered_urls: Vec<Vec<String>> = Vec::new();
+    // LinkedHashMap<HOST or URL, Vec<Target>>
+    let mut sorted_targets: LinkedHashMap<String, Vec<Target>> = LinkedHashMap::new();
+    let mut ordered_targets: Vec<Vec<Target>> = Vec::new();
 
-    for url in urls.iter() {
-        let parsed_url = Url::parse(url).unwrap();
-        let host = parsed_url.host_str().unwrap();
+    for target in targets.iter() {
+        let key = if by_host {
+            Url::parse(&target.url).unwrap().host_str().unwrap().to_owned()
+        } else {
+            target.url.to_owned()
+        };
 
-        if sorted_urls.contains_key(host) {
-            sorted_urls.get_mut(host).unwrap().push(url.to_owned());
+        if sorted_targets.contains_key(&key) {
+            sorted_targets.get_mut(&key).unwrap().push(target.to_owned());
         } else {
-            sorted_urls.insert(host.to_owned(), vec![url.to_owned()]);
+            sorted_targets.insert(key, vec![target.to_owned()]);
         }
     }
 
-    for host in sorted_urls.clone().keys() {
-        ordered_urls.push(sorted_urls[host].clone())
+    for key in sorted_targets.clone().keys() {
+        ordered_targets.push(sorted_targets[key].clone())
     }
 
-    ordered_urls
+    ordered_targets
 }
 
 /// returns true if more than 1 url is being checked a time